@@ -0,0 +1,175 @@
+use std::{
+    fmt::Display,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Runs per phase before measuring starts.
+    pub warmup: usize,
+    /// Measured runs per phase.
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub iterations: usize,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+        let iterations = samples.len();
+        let median = match iterations {
+            0 => Duration::ZERO,
+            n if n % 2 == 0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+            n => samples[n / 2],
+        };
+        Self {
+            min: samples.first().copied().unwrap_or_default(),
+            median,
+            mean: samples.iter().sum::<Duration>() / iterations.max(1) as u32,
+            iterations,
+        }
+    }
+
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"min_ns": {}, "median_ns": {}, "mean_ns": {}, "iterations": {}}}"#,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.mean.as_nanos(),
+            self.iterations
+        )
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>12} median {:>12} mean {:>12}",
+            format!("{:.1?}", self.min),
+            format!("{:.1?}", self.median),
+            format!("{:.1?}", self.mean)
+        )
+    }
+}
+
+/// Runs `phase` `config.warmup` times, then times it `config.iterations` times.
+pub fn measure<T>(config: &BenchConfig, mut phase: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(phase());
+    }
+    Stats::from_samples(
+        (0..config.iterations)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(phase());
+                start.elapsed()
+            })
+            .collect(),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayBenchmark {
+    pub day: usize,
+    pub title: &'static str,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBenchmark {
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{"day": {}, "title": "{}", "parse": {}, "part1": {}, "part2": {}}}"#,
+            self.day,
+            self.title.replace('\\', "\\\\").replace('"', "\\\""),
+            self.parse.to_json(),
+            self.part1.to_json(),
+            self.part2.to_json()
+        )
+    }
+}
+
+impl Display for DayBenchmark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Day {}: {}", self.day, self.title)?;
+        writeln!(f, "  parse  {}", self.parse)?;
+        writeln!(f, "  part 1 {}", self.part1)?;
+        write!(f, "  part 2 {}", self.part2)
+    }
+}
+
+/// Benchmarks of several days, serialized as JSON to compare them between commits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    pub days: Vec<DayBenchmark>,
+}
+
+impl BenchReport {
+    pub fn to_json(&self) -> String {
+        let days = self
+            .days
+            .iter()
+            .map(|day| format!("    {}", day.to_json()))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        format!(
+            "{{\n  \"warmup\": {},\n  \"iterations\": {},\n  \"days\": [\n{days}\n  ]\n}}\n",
+            self.config.warmup, self.config.iterations
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_stats() {
+        let stats = Stats::from_samples(
+            [4, 1, 3, 2]
+                .into_iter()
+                .map(Duration::from_micros)
+                .collect(),
+        );
+        assert_eq!(Duration::from_micros(1), stats.min);
+        assert_eq!(Duration::from_nanos(2500), stats.median);
+        assert_eq!(Duration::from_nanos(2500), stats.mean);
+        assert_eq!(4, stats.iterations);
+    }
+
+    #[test]
+    fn benchmarks_through_registry() {
+        let config = BenchConfig {
+            warmup: 1,
+            iterations: 3,
+        };
+        let day = crate::solution::get(1).unwrap();
+        let benchmark = day.bench("3   4\n4   3\n2   5", &config).unwrap();
+        assert_eq!(3, benchmark.part2.iterations);
+        let json = BenchReport {
+            config,
+            days: vec![benchmark],
+        }
+        .to_json();
+        assert!(json.contains(r#""day": 1, "title": "Historian Hysteria", "parse": {"min_ns": "#));
+    }
+}
@@ -0,0 +1,150 @@
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::error::{ParseContext, ParseError};
+
+/// Lists of numbers written side by side, one column per list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lists {
+    /// Every column sorted ascending.
+    columns: Vec<Vec<i64>>,
+}
+
+impl Lists {
+    /// Reads lines of exactly `columns` whitespace separated numbers.
+    pub fn parse(ctx: &ParseContext, input: &str, columns: usize) -> Result<Self, ParseError> {
+        let mut lists = vec![vec![]; columns];
+        for line in input.lines() {
+            let numbers = line.split_whitespace().collect_vec();
+            if numbers.len() != columns {
+                return Err(ctx.error(
+                    line,
+                    format!("Expected {columns} numbers, found {}", numbers.len()),
+                ));
+            }
+            for (list, number) in lists.iter_mut().zip(numbers) {
+                list.push(ctx.parse::<i64>(number)?);
+            }
+        }
+        for list in &mut lists {
+            list.par_sort_unstable();
+        }
+        Ok(Self { columns: lists })
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Numbers of column `idx`, sorted ascending.
+    pub fn column(&self, idx: usize) -> &[i64] {
+        &self.columns[idx]
+    }
+
+    /// Sum of the distances between the smallest numbers of both columns, the second smallest
+    /// and so on.
+    pub fn distance(&self, lhs: usize, rhs: usize) -> i64 {
+        self.column(lhs)
+            .iter()
+            .zip(self.column(rhs))
+            .map(|(a, b)| (a - b).abs())
+            .sum()
+    }
+
+    /// Sum of the numbers of column `lhs`, each multiplied with how often it appears in column
+    /// `rhs`.
+    pub fn similarity(&self, lhs: usize, rhs: usize) -> i64 {
+        self.matches(lhs, rhs)
+            .map(|(number, lhs, rhs)| number * (lhs * rhs) as i64)
+            .sum()
+    }
+
+    /// Middle number of column `idx`, the mean of both middle numbers for an even count. `None`
+    /// for an empty column.
+    pub fn median(&self, idx: usize) -> Option<f64> {
+        let column = self.column(idx);
+        let mid = column.len() / 2;
+        match column.len() {
+            0 => None,
+            len if len % 2 == 1 => Some(column[mid] as f64),
+            _ => Some((column[mid - 1] + column[mid]) as f64 / 2.0),
+        }
+    }
+
+    /// Distinct numbers of column `idx` with how often they appear, ascending.
+    pub fn histogram(&self, idx: usize) -> Vec<(i64, usize)> {
+        self.column(idx)
+            .iter()
+            .dedup_with_count()
+            .map(|(count, number)| (*number, count))
+            .collect()
+    }
+
+    /// Number of pairs of equal numbers that can be formed between both columns, using every
+    /// number at most once.
+    pub fn overlap(&self, lhs: usize, rhs: usize) -> usize {
+        self.matches(lhs, rhs)
+            .map(|(_, lhs, rhs)| lhs.min(rhs))
+            .sum()
+    }
+
+    /// Sum of the differences in how often each number appears in both columns, i.e. how many
+    /// numbers are left without a partner by [`Lists::overlap`].
+    pub fn histogram_distance(&self, lhs: usize, rhs: usize) -> usize {
+        self.column(lhs).len() + self.column(rhs).len() - 2 * self.overlap(lhs, rhs)
+    }
+
+    /// Numbers appearing in both columns, with how often they appear in each.
+    fn matches(&self, lhs: usize, rhs: usize) -> impl Iterator<Item = (i64, usize, usize)> {
+        let (lhs, rhs) = (self.histogram(lhs), self.histogram(rhs));
+        let mut rhs = rhs.into_iter().peekable();
+        lhs.into_iter().filter_map(move |(number, count)| {
+            while rhs.next_if(|(other, _)| *other < number).is_some() {}
+            match rhs.peek() {
+                Some((other, other_count)) if *other == number => {
+                    Some((number, count, *other_count))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str, columns: usize) -> Result<Lists, ParseError> {
+        Lists::parse(&ParseContext::new(1, input), input, columns)
+    }
+
+    #[test]
+    fn reads_columns() {
+        let lists = parse("3 4 1\n4 3 1\n2 5 9\n", 3).unwrap();
+        assert_eq!(3, lists.columns());
+        assert_eq!([1, 1, 9], lists.column(2));
+        assert_eq!(8, lists.distance(0, 2));
+        assert_eq!(
+            "Day 1, line 2, column 1: Expected 2 numbers, found 3 (`4 3 1`)",
+            parse("3 4\n4 3 1\n", 2)
+                .unwrap_err()
+                .to_string()
+                .lines()
+                .next()
+                .unwrap()
+        );
+        assert!(parse("3 x\n", 2).is_err());
+    }
+
+    #[test]
+    fn computes_statistics() {
+        let lists = parse("3 4\n4 3\n2 5\n1 3\n3 9\n3 3\n", 2).unwrap();
+        assert_eq!(Some(3.0), lists.median(0));
+        assert_eq!(Some(3.5), lists.median(1));
+        assert_eq!(vec![(3, 3), (4, 1), (5, 1), (9, 1)], lists.histogram(1));
+        assert_eq!(31, lists.similarity(0, 1));
+        assert_eq!(4, lists.overlap(0, 1));
+        assert_eq!(4, lists.histogram_distance(0, 1));
+        assert_eq!(None, parse("", 2).unwrap().median(0));
+    }
+}
@@ -1,60 +1,60 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
-use fxhash::FxHashMap;
-use itertools::multiunzip;
-#[allow(unused)]
-use itertools::Itertools;
-use rayon::prelude::*;
+
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
+mod lists;
+
+pub use lists::Lists;
 
 type Output = i64;
-type Input = (Vec<i64>, Vec<i64>);
+type Input = Lists;
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Input {
-    multiunzip(
-        input
-            .lines()
-            .flat_map(|line| {
-                line.split_whitespace()
-                    .map(|n| n.parse::<i64>().unwrap())
-                    .tuple_windows()
-            })
-            .collect::<Vec<(i64, i64)>>(),
-    )
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Lists::parse(&ParseContext::new(1, input), input, 2)
 }
 
 #[aoc(day1, part1)]
 pub fn solve_part1(input: &Input) -> Output {
-    let mut left = input.0.clone();
-    let mut right = input.1.clone();
-    left.par_sort_unstable();
-    right.par_sort_unstable();
-    left.iter()
-        .zip(right.iter())
-        .map(|(a, b)| (a - b).abs())
-        .sum()
+    input.distance(0, 1)
 }
 
 #[aoc(day1, part2)]
 pub fn solve_part2(input: &Input) -> Output {
-    let num_count = input.1.iter().fold(FxHashMap::default(), |mut map, num| {
-        map.entry(num)
-            .and_modify(|frequency| *frequency += 1)
-            .or_insert(1);
-        map
-    });
-    input
-        .0
-        .iter()
-        .map(|num| num * num_count.get(num).unwrap_or(&0))
-        .sum()
+    input.similarity(0, 1)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: usize = 1;
+    const TITLE: &'static str = "Historian Hysteria";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -73,11 +73,11 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(11, solve_part1(&input_generator(sample())));
+        assert_eq!(11, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(31, solve_part2(&input_generator(sample())));
+        assert_eq!(31, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
@@ -1,3 +1,4 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
@@ -6,18 +7,30 @@ use pathfinding::{
     prelude::{bfs_reach, yen},
 };
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Num = usize;
 type Output = Num;
 type Input = Matrix<Num>;
 
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Input {
-    Matrix::from_rows(
-        input
-            .lines()
-            .map(|line| line.chars().map(|n| n.to_digit(10).unwrap() as Num)),
-    )
-    .unwrap()
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(10, input);
+    ctx.check_rectangular(input)?;
+    let rows = input
+        .lines()
+        .map(|line| {
+            line.char_indices()
+                .map(|(idx, n)| {
+                    n.to_digit(10)
+                        .map(|n| n as Num)
+                        .ok_or_else(|| ctx.error(&line[idx..idx + n.len_utf8()], "Not a height"))
+                })
+                .collect::<Result<Vec<Num>, ParseError>>()
+        })
+        .collect::<Result<Vec<Vec<Num>>, ParseError>>()?;
+    Matrix::from_rows(rows).map_err(|err| ctx.error(input, err))
 }
 
 #[aoc(day10, part1)]
@@ -77,12 +90,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Hoof It";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -102,11 +138,11 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(36, solve_part1(&input_generator(sample())));
+        assert_eq!(36, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(81, solve_part2(&input_generator(sample())));
+        assert_eq!(81, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
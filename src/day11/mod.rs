@@ -1,27 +1,34 @@
 use std::collections::HashMap;
 
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Num = usize;
 type Output = Num;
 type Input = HashMap<Num, Num>;
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> Input {
-    input.split_whitespace().fold(HashMap::new(), |mut map, n| {
-        map.entry(n.parse().unwrap())
-            .and_modify(|count| *count += 1)
-            .or_insert(1);
-        map
-    })
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(11, input);
+    input
+        .split_whitespace()
+        .try_fold(HashMap::new(), |mut map, n| {
+            map.entry(ctx.parse(n)?)
+                .and_modify(|count| *count += 1)
+                .or_insert(1);
+            Ok(map)
+        })
 }
 
 fn transform_stone(num: Num) -> (Num, Option<Num>) {
     match num {
         0 => (1, None),
-        n if (num.ilog10() + 1) % 2 == 0 => {
+        n if (num.ilog10() + 1).is_multiple_of(2) => {
             let digits = num.ilog10() + 1; // As num must not be 0 it can only be precomputed here
             (
                 n / 10_usize.pow(digits / 2),
@@ -68,12 +75,35 @@ pub fn solve_part2(input: &Input) -> Output {
     observ(input, 75)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Plutonian Pebbles";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -86,11 +116,14 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(55312, solve_part1(&input_generator(sample())));
+        assert_eq!(55312, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(65601038650482, solve_part2(&input_generator(sample())));
+        assert_eq!(
+            65601038650482,
+            solve_part2(&input_generator(sample()).unwrap())
+        );
     }
 }
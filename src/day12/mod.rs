@@ -1,10 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
-use crate::utils::point::Point;
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{grid::Grid, point::Point};
 
 type Num = usize;
 type Output = Num;
@@ -12,48 +15,29 @@ type Pos = Point;
 type Input = Vec<HashSet<Point>>;
 
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Input {
-    let garden: HashMap<Point, char> = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(x, c)| (Point::from((x as isize, y as isize)), c))
-        })
-        .collect();
-    let mut regions: HashMap<Point, HashSet<Point>> = garden
-        .keys()
-        .map(|pos| (*pos, HashSet::from_iter(vec![*pos])))
-        .collect::<HashMap<_, _>>();
-
-    let directions = [
-        Point::from((1, 0)),
-        Point::from((0, 1)),
-        Point::from((-1, 0)),
-        Point::from((0, -1)),
-    ];
-    for plant in garden.keys() {
-        for dir in directions {
-            if garden.contains_key(&(plant + &&dir)) && garden[plant] == garden[&(plant + &&dir)] {
-                *regions.get_mut(plant).unwrap() = regions[plant]
-                    .union(&regions[&(plant + &&dir)])
-                    .cloned()
-                    .collect();
-                for other_plants in &regions[plant].clone() {
-                    *regions.get_mut(other_plants).unwrap() = regions[plant].clone();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let garden = Grid::parse(&ParseContext::new(12, input), input, Some)?;
+    let mut visited = Grid::new(garden.width(), garden.height(), false);
+    let mut regions = vec![];
+    for start in garden.positions() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut region = HashSet::from([start]);
+        let mut todo = vec![start];
+        while let Some(plant) = todo.pop() {
+            for neighbour in garden.neighbours(plant) {
+                if !visited[neighbour] && garden[neighbour] == garden[start] {
+                    visited[neighbour] = true;
+                    region.insert(neighbour);
+                    todo.push(neighbour);
                 }
             }
         }
+        regions.push(region);
     }
-    regions.values().fold(Vec::new(), |mut urs, region| {
-        if !urs.contains(region) {
-            urs.push(region.clone());
-            urs
-        } else {
-            urs
-        }
-    })
+    Ok(regions)
 }
 
 fn area(region: &HashSet<Pos>) -> Num {
@@ -139,12 +123,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: usize = 12;
+    const TITLE: &'static str = "Garden Groups";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -166,11 +173,11 @@ MMMISSJEEE"
 
     #[test]
     fn samples_part1() {
-        assert_eq!(1930, solve_part1(&input_generator(sample())));
+        assert_eq!(1930, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(1206, solve_part2(&input_generator(sample())));
+        assert_eq!(1206, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
@@ -1,10 +1,12 @@
 use std::str::FromStr;
 
-use anyhow::{Context, Error, Result};
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{CaptureError, ParseContext, ParseError};
+use crate::solution::Solution;
 use crate::utils::point::Point;
 
 type Num = isize;
@@ -31,19 +33,19 @@ impl Machine {
     /// requiring
     /// $$ a_x * b_y \neq{} a_y * b_x $$ and $$a_x \neq{} 0 $$
     pub fn solve(&self) -> Option<(isize, isize)> {
-        if self.a_move['x'] == 0 {
+        if self.a_move.x() == 0 {
             return None;
         }
-        if self.a_move['x'] * self.b_move['y'] == self.a_move['y'] * self.b_move['x'] {
+        if self.a_move.x() * self.b_move.y() == self.a_move.y() * self.b_move.x() {
             return None;
         }
-        let b = (self.a_move['y'] * self.price['x'] - self.a_move['x'] * self.price['y'])
-            / (self.a_move['y'] * self.b_move['x'] - self.a_move['x'] * self.b_move['y']);
+        let b = (self.a_move.y() * self.price.x() - self.a_move.x() * self.price.y())
+            / (self.a_move.y() * self.b_move.x() - self.a_move.x() * self.b_move.y());
 
-        let a = (self.price['x'] - b * self.b_move['x']) / self.a_move['x'];
+        let a = (self.price.x() - b * self.b_move.x()) / self.a_move.x();
 
-        if self.price['x'] == a * self.a_move['x'] + b * self.b_move['x']
-            && self.price['y'] == a * self.a_move['y'] + b * self.b_move['y']
+        if self.price.x() == a * self.a_move.x() + b * self.b_move.x()
+            && self.price.y() == a * self.a_move.y() + b * self.b_move.y()
         {
             Some((a, b))
         } else {
@@ -55,72 +57,37 @@ impl Machine {
         Self {
             a_move: self.a_move,
             b_move: self.b_move,
-            price: Point::from((self.price['x'] + constant, self.price['y'] + constant)),
+            price: Point::from((self.price.x() + constant, self.price.y() + constant)),
         }
     }
 }
 
 impl FromStr for Machine {
-    type Err = Error;
+    type Err = CaptureError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re = regex!(
-            r"Button A: X\+(?<ax>\d+), Y\+(?<ay>\d+)\nButton B: X\+(?<bx>\d+), Y\+(?<by>\d+)\nPrize: X=(?<px>\d+), Y=(?<py>\d+)"
-        );
-        let capured = re.captures(s).context("Regex not capturing")?;
+        let caps = regex_parse!(
+            r"Button A: X\+(?<ax>\d+), Y\+(?<ay>\d+)\nButton B: X\+(?<bx>\d+), Y\+(?<by>\d+)\nPrize: X=(?<px>\d+), Y=(?<py>\d+)",
+            s,
+            { ax: Num, ay: Num, bx: Num, by: Num, px: Num, py: Num }
+        )?;
         Ok(Self {
-            a_move: Point::from((
-                capured
-                    .name("ax")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-                capured
-                    .name("ay")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-            )),
-            b_move: Point::from((
-                capured
-                    .name("bx")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-                capured
-                    .name("by")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-            )),
-            price: Point::from((
-                capured
-                    .name("px")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-                capured
-                    .name("py")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-            )),
+            a_move: Point::from((caps.ax, caps.ay)),
+            b_move: Point::from((caps.bx, caps.by)),
+            price: Point::from((caps.px, caps.py)),
         })
     }
 }
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Result<Input> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(13, input);
     input
         .split("\n\n")
-        .map(Machine::from_str)
-        .collect::<Result<Vec<Machine>>>()
+        .map(|machine| {
+            Machine::from_str(machine).map_err(|err| ctx.error(err.locate(machine), err))
+        })
+        .collect::<Result<Vec<Machine>, ParseError>>()
 }
 
 #[aoc(day13, part1)]
@@ -142,12 +109,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input).unwrap())
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input).unwrap())
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: usize = 13;
+    const TITLE: &'static str = "Claw Contraption";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -1,11 +1,13 @@
 use std::{cmp::Ordering, str::FromStr};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
-use crate::utils::point::Point;
+use crate::error::{CaptureError, ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{grid::Grid, point::Point};
 
 type Output = i64;
 type Input = (Vec<Robot>, Point);
@@ -20,51 +22,29 @@ pub struct Robot {
 }
 
 impl FromStr for Robot {
-    type Err = anyhow::Error;
+    type Err = CaptureError;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let re = regex!(r"p=(?<px>\d+),(?<py>\d+) v=(?<vx>-?\d+),(?<vy>-?\d+)");
-        let captured = re.captures(s).context("Regex not capturing")?;
+        let caps = regex_parse!(
+            r"p=(?<px>\d+),(?<py>\d+) v=(?<vx>-?\d+),(?<vy>-?\d+)",
+            s,
+            { px: isize, py: isize, vx: isize, vy: isize }
+        )?;
         Ok(Self {
-            position: Point::from((
-                captured
-                    .name("px")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-                captured
-                    .name("py")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-            )),
-            speed: Point::from((
-                captured
-                    .name("vx")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-                captured
-                    .name("vy")
-                    .context("Group not captured")?
-                    .as_str()
-                    .parse()
-                    .context("Not a number")?,
-            )),
+            position: Point::from((caps.px, caps.py)),
+            speed: Point::from((caps.vx, caps.vy)),
         })
     }
 }
 
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Result<Input> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(14, input);
     Ok((
         input
             .lines()
-            .map(Robot::from_str)
-            .collect::<Result<Vec<Robot>>>()?,
+            .map(|line| Robot::from_str(line).map_err(|err| ctx.error(err.locate(line), err)))
+            .collect::<Result<Vec<Robot>, ParseError>>()?,
         Point::from((X_LIM, Y_LIM)),
     ))
 }
@@ -78,11 +58,11 @@ impl Robot {
     }
 
     pub fn get_quadrant(&self, limits: Point) -> Option<usize> {
-        let half_point_x = limits['x'] / 2;
-        let half_point_y = limits['y'] / 2;
+        let half_point_x = limits.x() / 2;
+        let half_point_y = limits.y() / 2;
         match (
-            self.position['x'].cmp(&half_point_x),
-            self.position['y'].cmp(&half_point_y),
+            self.position.x().cmp(&half_point_x),
+            self.position.y().cmp(&half_point_y),
         ) {
             (Ordering::Less, Ordering::Less) => Some(0),
             (Ordering::Less, Ordering::Greater) => Some(1),
@@ -110,21 +90,17 @@ pub fn solve_part1(input: &Input) -> Output {
     quadrants.iter().product()
 }
 
-fn print_robots(robots: &[Robot], limits: Point) {
-    for y in 0..limits['y'] {
-        for x in 0..limits['x'] {
-            if robots
-                .iter()
-                .map(|r| r.position)
-                .contains(&Point::from((x, y)))
-            {
-                print!("x");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+/// The robots after `seconds` seconds, as the number of robots on each tile with `.` for none.
+pub fn render(input: &Input, seconds: isize) -> Grid<char> {
+    let (robots, limits) = input;
+    let mut map = Grid::new(limits.x() as usize, limits.y() as usize, 0);
+    for robot in robots {
+        map[robot.drive(seconds, *limits).position] += 1;
     }
+    map.map(|count| match count {
+        0 => '.',
+        n => char::from_digit(*n.min(&9), 10).unwrap(),
+    })
 }
 
 #[aoc(day14, part2)]
@@ -140,7 +116,6 @@ pub fn solve_part2(input: &Input) -> Output {
             .collect::<Vec<Robot>>();
         seconds += 1;
         if map.iter().map(|robot| robot.position).unique().count() == robots {
-            print_robots(&map, *limits);
             break;
         }
     }
@@ -148,12 +123,35 @@ pub fn solve_part2(input: &Input) -> Output {
     seconds
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input).unwrap())
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input).unwrap())
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: usize = 14;
+    const TITLE: &'static str = "Restroom Redoubt";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -177,13 +175,34 @@ p=9,5 v=-3,-3"
 
     #[test]
     fn samples_part1() {
-        let (map, _) = input_generator(&sample()).unwrap();
+        let (map, _) = input_generator(sample()).unwrap();
         let limits = Point::from((11, 7));
         assert_eq!(12, solve_part1(&(map, limits)));
     }
 
     #[test]
-    fn samples_part2() {
-        assert!(true);
+    fn renders_robots() {
+        let (map, _) = input_generator(sample()).unwrap();
+        let input = (map, Point::from((11, 7)));
+        assert_eq!(
+            "1.12.......
+...........
+...........
+......11.11
+1.1........
+.........1.
+.......1...",
+            render(&input, 0).to_string().trim_end()
+        );
+        assert_eq!(
+            "......2..1.
+...........
+1..........
+.11........
+.....1.....
+...12......
+.1....1....",
+            render(&input, 100).to_string().trim_end()
+        );
     }
 }
@@ -1,13 +1,16 @@
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
+use anyhow::{bail, Result};
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
-use crate::utils::point::Point;
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{direction::Direction, grid::Grid, point::Point};
 
 type Output = isize;
-type Map = HashMap<Point, Position>;
+type Map = Grid<Position>;
 type Commands = Vec<Direction>;
 type Input = (Map, Commands);
 
@@ -19,207 +22,158 @@ pub enum Position {
     Robot,
 }
 
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Position::Empty => '.',
-                Position::Wall => '#',
-                Position::Cargo => 'O',
-                Position::Robot => '@',
-            }
-        )
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-pub enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl From<char> for Direction {
-    fn from(value: char) -> Self {
+impl From<Position> for char {
+    fn from(value: Position) -> Self {
         match value {
-            '^' => Self::Up,
-            '>' => Self::Right,
-            'v' => Self::Down,
-            '<' => Self::Left,
-            d => panic!("Unkown direction: {}", d),
+            Position::Empty => '.',
+            Position::Wall => '#',
+            Position::Cargo => 'O',
+            Position::Robot => '@',
         }
     }
 }
 
-impl From<&Direction> for Point {
-    fn from(value: &Direction) -> Self {
-        match value {
-            Direction::Up => Point::from((0, -1)),
-            Direction::Right => Point::from((1, 0)),
-            Direction::Down => Point::from((0, 1)),
-            Direction::Left => Point::from((-1, 0)),
-        }
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", char::from(*self))
     }
 }
 
-impl From<char> for Position {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Position {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '.' => Self::Empty,
-            '#' => Self::Wall,
-            'O' => Self::Cargo,
-            '@' => Self::Robot,
-            o => panic!("Unkown space occupant: {}", o),
+            '.' => Ok(Self::Empty),
+            '#' => Ok(Self::Wall),
+            'O' => Ok(Self::Cargo),
+            '@' => Ok(Self::Robot),
+            o => bail!("Unkown space occupant: {}", o),
         }
     }
 }
 
-#[aoc_generator(day15, part1)]
-pub fn input_generator(input: &str) -> Input {
-    let (map, commands) = input.split_once("\n\n").unwrap();
-    let map = map
-        .lines()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.chars()
-                .enumerate()
-                .map(move |(x, c)| (Point::from((x as isize, y as isize)), Position::from(c)))
+fn parse_commands(ctx: &ParseContext, commands: &str) -> Result<Commands, ParseError> {
+    commands
+        .char_indices()
+        .filter(|(_, c)| *c != '\n')
+        .map(|(idx, c)| {
+            Direction::from_arrow(c)
+                .ok_or_else(|| ctx.error(&commands[idx..idx + c.len_utf8()], "Unknown direction"))
         })
-        .collect::<HashMap<Point, Position>>();
+        .collect()
+}
 
-    let commands = commands
-        .chars()
-        .filter(|c| *c != '\n')
-        .map(Direction::from)
-        .collect::<Vec<Direction>>();
+#[aoc_generator(day15, part1)]
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(15, input);
+    let (map, commands) = ctx.split_once(input, "\n\n")?;
+    let map = Grid::parse(&ctx, map, |c| Position::try_from(c).ok())?;
+
+    let commands = parse_commands(&ctx, commands)?;
 
-    (map, commands)
+    Ok((map, commands))
 }
 
 pub fn shift(pos: Point, dir: &Direction, map: &mut Map) -> Option<Point> {
-    let new_pos = pos + Point::from(dir);
-    if map[&new_pos] == Position::Wall {
+    let new_pos = pos + Point::from(*dir);
+    if map[new_pos] == Position::Wall {
         return None;
     }
-    if map[&new_pos] == Position::Cargo {
-        if let Some(nnp) = shift(new_pos, dir, map) {
-            *map.get_mut(&nnp).unwrap() = Position::Cargo;
-            *map.get_mut(&new_pos).unwrap() = Position::Empty;
-        } else {
-            return None;
-        }
+    if map[new_pos] == Position::Cargo {
+        let nnp = shift(new_pos, dir, map)?;
+        map[nnp] = Position::Cargo;
+        map[new_pos] = Position::Empty;
     }
     Some(new_pos)
 }
 
 pub fn compute_gps(pos: &Point) -> Output {
-    pos['y'] * 100 + pos['x']
+    pos.y() * 100 + pos.x()
 }
 
-pub fn print_map<T>(map: &HashMap<Point, T>)
-where
-    T: Display,
-{
-    let x_max = map.keys().map(|pos| pos['x']).max().unwrap();
-    let y_max = map.keys().map(|pos| pos['y']).max().unwrap();
-
-    for y in 0..=y_max {
-        for x in 0..=x_max {
-            print!("{}", map[&Point::from((x, y))]);
-        }
-        println!();
-    }
-}
-
-#[aoc(day15, part1)]
-pub fn solve_part1(input: &Input) -> Output {
+/// The warehouse after the first `steps` commands.
+fn simulate(input: &Input, steps: usize) -> Map {
     let (map, commands) = input;
     let mut map = map.clone();
 
-    let mut pos = *map
-        .iter()
-        .find(|(_, occ)| **occ == Position::Robot)
-        .unwrap()
-        .0;
+    let mut pos = map.find(&Position::Robot).unwrap();
 
-    for command in commands {
+    for command in commands.iter().take(steps) {
         let tmp = map.clone();
         if let Some(new_pos) = shift(pos, command, &mut map) {
-            *map.get_mut(&new_pos).unwrap() = Position::Robot;
-            *map.get_mut(&pos).unwrap() = Position::Empty;
+            map[new_pos] = Position::Robot;
+            map[pos] = Position::Empty;
             pos = new_pos;
         } else {
             map = tmp;
         }
     }
+    map
+}
 
-    map.iter()
-        .filter(|(_, occ)| **occ == Position::Cargo)
-        .map(|(pos, _)| compute_gps(pos))
+/// The warehouse after the first `steps` commands.
+pub fn render(input: &Input, steps: usize) -> Grid<char> {
+    simulate(input, steps).map(|occ| char::from(*occ))
+}
+
+#[aoc(day15, part1)]
+pub fn solve_part1(input: &Input) -> Output {
+    simulate(input, input.1.len())
+        .find_all(&Position::Cargo)
+        .map(|pos| compute_gps(&pos))
         .sum()
 }
 
-type Map2 = HashMap<Point, char>;
+type Map2 = Grid<char>;
 type Input2 = (Map2, Commands);
 
 #[aoc_generator(day15, part2)]
-pub fn input_generator2(input: &str) -> Input2 {
-    let (map, commands) = input.split_once("\n\n").unwrap();
-    let map = map
-        .replace("#", "##")
-        .replace("O", "[]")
-        .replace(".", "..")
-        .replace("@", "@.");
-    let map = map
-        .lines()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.chars()
-                .enumerate()
-                .map(move |(x, c)| (Point::from((x as isize, y as isize)), c))
-        })
-        .collect::<HashMap<Point, char>>();
-
-    let commands = commands
-        .chars()
-        .filter(|c| *c != '\n')
-        .map(Direction::from)
-        .collect::<Vec<Direction>>();
-
-    (map, commands)
+pub fn input_generator2(input: &str) -> Result<Input2, ParseError> {
+    let (map, commands) = input_generator(input)?;
+    let map = Grid::from_rows((0..map.height()).map(|y| {
+        map.row(y)
+            .flat_map(|occ| match occ {
+                Position::Empty => ['.', '.'],
+                Position::Wall => ['#', '#'],
+                Position::Cargo => ['[', ']'],
+                Position::Robot => ['@', '.'],
+            })
+            .collect()
+    }))
+    .expect("Widened rows have the same length");
+
+    Ok((map, commands))
 }
 
 pub fn shift2(pos: Point, dir: &Direction, map: &mut Map2) -> Option<Point> {
-    let new_pos = pos + Point::from(dir);
+    let new_pos = pos + Point::from(*dir);
 
-    if map[&new_pos] == '#' {
+    if map[new_pos] == '#' {
         return None;
     }
 
-    if map[&new_pos] == '[' {
-        shift2(new_pos + Point::from(&Direction::Right), dir, map)?;
+    if map[new_pos] == '[' {
+        shift2(new_pos + Direction::Right.into(), dir, map)?;
         shift2(new_pos, dir, map)?;
     }
-    if map[&new_pos] == ']' {
-        shift2(new_pos + Point::from(&Direction::Left), dir, map)?;
+    if map[new_pos] == ']' {
+        shift2(new_pos + Direction::Left.into(), dir, map)?;
         shift2(new_pos, dir, map)?;
     }
 
-    (*map.get_mut(&new_pos).unwrap(), *map.get_mut(&pos).unwrap()) = (map[&pos], map[&new_pos]);
+    (map[new_pos], map[pos]) = (map[pos], map[new_pos]);
     Some(new_pos)
 }
 
-#[aoc(day15, part2)]
-pub fn solve_part2(input: &Input2) -> Output {
+/// The widened warehouse after the first `steps` commands.
+fn simulate2(input: &Input2, steps: usize) -> Map2 {
     let (map, commands) = input;
     let mut map = map.clone();
 
-    let mut pos = *map.iter().find(|(_, occ)| **occ == '@').unwrap().0;
+    let mut pos = map.find(&'@').unwrap();
 
-    for command in commands {
+    for command in commands.iter().take(steps) {
         let tmp = map.clone();
         if let Some(new_pos) = shift2(pos, command, &mut map) {
             pos = new_pos;
@@ -227,19 +181,51 @@ pub fn solve_part2(input: &Input2) -> Output {
             map = tmp;
         }
     }
+    map
+}
+
+/// The widened warehouse after the first `steps` commands.
+pub fn render2(input: &Input2, steps: usize) -> Grid<char> {
+    simulate2(input, steps)
+}
 
-    map.iter()
-        .filter(|(_, occ)| **occ == '[')
-        .map(|(pos, _)| compute_gps(pos))
+#[aoc(day15, part2)]
+pub fn solve_part2(input: &Input2) -> Output {
+    simulate2(input, input.1.len())
+        .find_all(&'[')
+        .map(|pos| compute_gps(&pos))
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator2(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator2(input)?))
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: usize = 15;
+    const TITLE: &'static str = "Warehouse Woes";
+
+    type Input = (Input, Input2);
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((input_generator(input)?, input_generator2(input)?))
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(&input.0))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(&input.1))
+    }
 }
 
 #[cfg(test)]
@@ -272,11 +258,45 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"
 
     #[test]
     fn samples_part1() {
-        assert_eq!(10092, solve_part1(&input_generator(sample())));
+        assert_eq!(10092, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(9021, solve_part2(&input_generator2(sample())));
+        assert_eq!(9021, solve_part2(&input_generator2(sample()).unwrap()));
+    }
+
+    #[test]
+    fn renders_warehouse() {
+        let input = input_generator(sample()).unwrap();
+        assert_eq!(Some(Point::from((4, 4))), render(&input, 0).find(&'@'));
+        assert_eq!(
+            "##########
+#.O.O.OOO#
+#........#
+#OO......#
+#OO@.....#
+#O#.....O#
+#O.....OO#
+#O.....OO#
+#OO....OO#
+##########",
+            render(&input, input.1.len()).to_string().trim_end()
+        );
+
+        let input = input_generator2(sample()).unwrap();
+        assert_eq!(
+            "####################
+##[].......[].[][]##
+##[]...........[].##
+##[]........[][][]##
+##[]......[]....[]##
+##..##......[]....##
+##..[]............##
+##..@......[].[][]##
+##......[][]..[]..##
+####################",
+            render2(&input, input.1.len()).to_string().trim_end()
+        );
     }
 }
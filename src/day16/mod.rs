@@ -1,27 +1,33 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 use pathfinding::{directed::dijkstra, grid::Grid, prelude::astar_bag};
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{direction::Direction, grid::Grid as Map, point::Point};
+
 type Output = usize;
 type Cost = usize;
 type Pos = (isize, isize);
 type Input = (Grid, Pos, Pos);
 
 #[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> Input {
-    let start_index = input
-        .chars()
-        .filter(|c| *c != '\n')
-        .position(|c| c == 'S')
-        .unwrap();
-    let end_index = input
-        .chars()
-        .filter(|c| *c != '\n')
-        .position(|c| c == 'E')
-        .unwrap();
-
-    let x_size = input.lines().next().unwrap().len();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(16, input);
+    ctx.check_rectangular(input)?;
+    let find_tile = |tile: char| {
+        input
+            .chars()
+            .filter(|c| *c != '\n')
+            .position(|c| c == tile)
+            .ok_or_else(|| ctx.error(input, format!("No `{tile}` tile on the map")))
+    };
+    let start_index = find_tile('S')?;
+    let end_index = find_tile('E')?;
+
+    let x_size = input.lines().next().map_or(0, str::len);
 
     let start = (
         (start_index % x_size) as isize - 1,
@@ -45,30 +51,13 @@ pub fn input_generator(input: &str) -> Input {
             })
             .collect::<Vec<_>>(),
     )
-    .unwrap();
-
-    (grid, start, end)
-}
+    .ok_or_else(|| ctx.error(input, "No open tiles on the map"))?;
 
-fn turns(curr_dir: Pos, new_dir: Pos) -> usize {
-    // No turn
-    if curr_dir == new_dir {
-        return 0;
-    }
-    // Turn counterclockwise
-    if (-curr_dir.1, curr_dir.0) == new_dir {
-        return 1;
-    }
-    // Turn clockwise
-    if (curr_dir.1, -curr_dir.0) == new_dir {
-        return 1;
-    }
-    // Turn 180 deg
-    2
+    Ok((grid, start, end))
 }
 
-fn cost(curr_dir: Pos, new_dir: Pos) -> Cost {
-    match turns(curr_dir, new_dir) {
+fn cost(curr_dir: Direction, new_dir: Direction) -> Cost {
+    match curr_dir.turns(new_dir) {
         0 => 0,
         1 => 1000,
         2 => 2000, // Should never be taken as it would mean steping back, but generalizes the
@@ -77,14 +66,15 @@ fn cost(curr_dir: Pos, new_dir: Pos) -> Cost {
     }
 }
 
-fn neighbours(pos: Pos, dir: Pos, grid: &Grid) -> Vec<((Pos, Pos), Cost)> {
-    grid.neighbours((pos.0 as usize, pos.1 as usize))
-        .iter()
-        .map(|neigh| {
-            let next_dir = (neigh.0 as isize - pos.0, neigh.1 as isize - pos.1);
-            let cost = cost(dir, next_dir) + 1;
-            (((neigh.0 as isize, neigh.1 as isize), next_dir), cost)
+fn neighbours(pos: Pos, dir: Direction, grid: &Grid) -> Vec<((Pos, Direction), Cost)> {
+    Direction::ALL
+        .into_iter()
+        .map(|next_dir| {
+            let (x, y) = (Point::from(pos) + next_dir.into()).into();
+            ((x, y), next_dir)
         })
+        .filter(|((x, y), _)| *x >= 0 && *y >= 0 && grid.has_vertex((*x as usize, *y as usize)))
+        .map(|(next, next_dir)| ((next, next_dir), cost(dir, next_dir) + 1))
         .collect::<Vec<_>>()
 }
 
@@ -93,7 +83,7 @@ pub fn solve_part1(input: &Input) -> Output {
     let (grid, start, end) = input;
 
     let path = dijkstra::dijkstra(
-        &(*start, (1_isize, 0_isize)),
+        &(*start, Direction::Right),
         |(pos, dir)| neighbours(*pos, *dir, grid),
         |(pos, _)| *pos == *end,
     )
@@ -101,12 +91,12 @@ pub fn solve_part1(input: &Input) -> Output {
     path.1
 }
 
-#[aoc(day16, part2)]
-pub fn solve_part2(input: &Input) -> Output {
+/// Tiles on any of the best paths.
+fn best_tiles(input: &Input) -> Vec<Pos> {
     let (grid, start, end) = input;
 
     astar_bag(
-        &(*start, (1_isize, 0_isize)),
+        &(*start, Direction::Right),
         |(pos, dir)| neighbours(*pos, *dir, grid),
         |_| 1, // Does not really matter, just improves performance and must be lower than actual
         // cost
@@ -117,15 +107,60 @@ pub fn solve_part2(input: &Input) -> Output {
     .flatten()
     .map(|(pos, _)| (pos.0, pos.1))
     .unique()
-    .count()
+    .collect()
+}
+
+/// The maze with the tiles on any of the best paths marked `O`.
+pub fn render(input: &Input) -> Map<char> {
+    let (grid, start, end) = input;
+    // The grid does not contain the outer walls
+    let mut map = Map::new(grid.width + 2, grid.height + 2, '#');
+    let tile = |(x, y): Pos| Point::from((x + 1, y + 1));
+    for (x, y) in grid.iter() {
+        map[tile((x as isize, y as isize))] = '.';
+    }
+    for pos in best_tiles(input) {
+        map[tile(pos)] = 'O';
+    }
+    map[tile(*start)] = 'S';
+    map[tile(*end)] = 'E';
+    map
+}
+
+#[aoc(day16, part2)]
+pub fn solve_part2(input: &Input) -> Output {
+    best_tiles(input).len()
+}
+
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: usize = 16;
+    const TITLE: &'static str = "Reindeer Maze";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -172,21 +207,35 @@ mod tests {
 
     #[test]
     fn samples1_part1() {
-        assert_eq!(7036, solve_part1(&input_generator(sample1())));
+        assert_eq!(7036, solve_part1(&input_generator(sample1()).unwrap()));
     }
 
     #[test]
     fn samples2_part1() {
-        assert_eq!(11048, solve_part1(&input_generator(sample2())));
+        assert_eq!(11048, solve_part1(&input_generator(sample2()).unwrap()));
     }
 
     #[test]
     fn samples1_part2() {
-        assert_eq!(45, solve_part2(&input_generator(sample1())));
+        assert_eq!(45, solve_part2(&input_generator(sample1()).unwrap()));
     }
 
     #[test]
     fn samples2_part2() {
-        assert_eq!(64, solve_part2(&input_generator(sample2())));
+        assert_eq!(64, solve_part2(&input_generator(sample2()).unwrap()));
+    }
+
+    #[test]
+    fn renders_best_paths() {
+        let map = render(&input_generator(sample1()).unwrap());
+        assert_eq!(43, map.find_all(&'O').count());
+        assert_eq!(
+            sample1(),
+            map.map(|tile| match tile {
+                'O' => '.',
+                tile => *tile,
+            })
+            .to_string()
+        );
     }
 }
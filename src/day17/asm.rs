@@ -0,0 +1,186 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+use super::{
+    computer::{BitComp, Fault, Opcode},
+    Num,
+};
+
+const MNEMONICS: [(Opcode, &str); 8] = [
+    (Opcode::Adv, "adv"),
+    (Opcode::Bxl, "bxl"),
+    (Opcode::Bst, "bst"),
+    (Opcode::Jnz, "jnz"),
+    (Opcode::Bxc, "bxc"),
+    (Opcode::Out, "out"),
+    (Opcode::Bdv, "bdv"),
+    (Opcode::Cdv, "cdv"),
+];
+
+impl Display for Opcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (_, mnemonic) = MNEMONICS.iter().find(|(op, _)| op == self).unwrap();
+        write!(f, "{mnemonic}")
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MNEMONICS
+            .iter()
+            .find(|(_, mnemonic)| *mnemonic == s)
+            .map(|(op, _)| *op)
+            .with_context(|| format!("Unknown mnemonic `{s}`"))
+    }
+}
+
+/// An opcode with its raw operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub operand: Num,
+}
+
+impl Instruction {
+    /// Decodes the instruction at `pc`, which has to be inside of `memory`.
+    pub fn decode(memory: &[Num], pc: usize) -> Result<Self, Fault> {
+        let opcode =
+            Opcode::try_from(memory[pc]).map_err(|opcode| Fault::IllegalOpcode { pc, opcode })?;
+        let operand = *memory
+            .get(pc + 1)
+            .ok_or(Fault::TruncatedInstruction { pc })?;
+        Ok(Self { opcode, operand })
+    }
+}
+
+/// Shows combo operands 4 to 6 as the register they refer to, all other operands as numbers.
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.opcode.has_combo_operand(), self.operand) {
+            (true, 4) => write!(f, "{} A", self.opcode),
+            (true, 5) => write!(f, "{} B", self.opcode),
+            (true, 6) => write!(f, "{} C", self.opcode),
+            (_, operand) => write!(f, "{} {operand}", self.opcode),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((opcode, operand)) = s.split_whitespace().collect_tuple() else {
+            bail!("Expected `<mnemonic> <operand>`, found `{s}`");
+        };
+        let opcode = opcode.parse::<Opcode>()?;
+        let operand = match operand {
+            "A" | "B" | "C" if !opcode.has_combo_operand() => {
+                bail!("`{opcode}` takes a literal operand, found `{operand}`")
+            }
+            "A" => 4,
+            "B" => 5,
+            "C" => 6,
+            n => match n.parse() {
+                Ok(n @ 0..=7) => n,
+                _ => bail!("Operand `{n}` is not a register or a number from 0 to 7"),
+            },
+        };
+        Ok(Self { opcode, operand })
+    }
+}
+
+/// Lists the instructions of `memory`, one per line.
+pub fn disassemble(memory: &[Num]) -> Result<String> {
+    (0..memory.len())
+        .step_by(2)
+        .map(|pc| Ok(format!("{}\n", Instruction::decode(memory, pc)?)))
+        .collect()
+}
+
+/// Parses a listing of one instruction per line, as written by [`disassemble`]. Text after `;` is
+/// a comment.
+pub fn assemble(source: &str) -> Result<Vec<Num>> {
+    let mut memory = vec![];
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let instruction = line
+            .parse::<Instruction>()
+            .with_context(|| format!("Line {}", idx + 1))?;
+        memory.extend([instruction.opcode as Num, instruction.operand]);
+    }
+    Ok(memory)
+}
+
+impl BitComp {
+    /// Lists the program, see [`disassemble`].
+    pub fn disassemble(&self) -> Result<String> {
+        disassemble(&self.get_mem())
+    }
+
+    /// Creates a computer with all registers 0 running the program in `source`, see
+    /// [`assemble`].
+    pub fn assemble(source: &str) -> Result<Self> {
+        Ok(Self::new(0, 0, 0, assemble(source)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let memory = vec![2, 4, 1, 5, 7, 5, 4, 3, 1, 6, 0, 3, 5, 5, 3, 0];
+        let listing = disassemble(&memory).unwrap();
+        assert_eq!(
+            "bst A\nbxl 5\ncdv B\nbxc 3\nbxl 6\nadv 3\nout B\njnz 0\n",
+            listing
+        );
+        assert_eq!(memory, assemble(&listing).unwrap());
+        assert_eq!(vec![0, 7, 1, 4], assemble("adv 7\nbxl 4").unwrap());
+    }
+
+    #[test]
+    fn assembles_listings() {
+        let mut computer = BitComp::assemble(
+            "; prints A in octal
+            bst A
+            out B ; lowest digit
+            adv 3
+
+            jnz 0",
+        )
+        .unwrap();
+        computer.set_a(0o1234);
+        assert_eq!("4,3,2,1", computer.run().unwrap());
+    }
+
+    #[test]
+    fn reports_errors() {
+        let error = |source| format!("{:#}", assemble(source).unwrap_err());
+        assert_eq!("Line 2: Unknown mnemonic `mul`", error("adv 1\nmul 2"));
+        assert_eq!(
+            "Line 1: `bxl` takes a literal operand, found `B`",
+            error("bxl B")
+        );
+        assert_eq!(
+            "Line 1: Operand `8` is not a register or a number from 0 to 7",
+            error("out 8")
+        );
+        assert_eq!(
+            "Truncated instruction at 2",
+            disassemble(&[0, 1, 5]).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Illegal opcode 9 at 0",
+            disassemble(&[9, 1]).unwrap_err().to_string()
+        );
+    }
+}
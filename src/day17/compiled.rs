@@ -0,0 +1,278 @@
+use std::{fmt::Display, ops::ControlFlow};
+
+use itertools::Itertools;
+
+use super::{
+    asm::Instruction,
+    computer::{BitComp, Fault, Opcode, Register},
+    Num,
+};
+
+/// Index of a combo operand into the register file of [`Compiled::execute`], which holds the
+/// literals 0 to 3 followed by the registers.
+type Combo = u8;
+
+const A: usize = 4;
+const B: usize = 5;
+const C: usize = 6;
+
+/// Pre-decoded instruction, with the combo operand already checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Adv(Combo),
+    Bxl(Num),
+    Bst(Combo),
+    Jnz(usize),
+    Bxc,
+    Out(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
+    /// Executing this address faults, e.g. because of an illegal opcode. Index into
+    /// [`Compiled::faults`].
+    Fault(usize),
+}
+
+/// Decodes the instruction at `pc`, with any fault pushed to `faults`.
+fn decode(memory: &[Num], pc: usize, faults: &mut Vec<Fault>) -> Op {
+    let mut fault = |fault| {
+        faults.push(fault);
+        Op::Fault(faults.len() - 1)
+    };
+    let instruction = match Instruction::decode(memory, pc) {
+        Ok(instruction) => instruction,
+        Err(err) => return fault(err),
+    };
+    let combo = match instruction.operand {
+        operand @ 0..=6 => operand as Combo,
+        operand if instruction.opcode.has_combo_operand() => {
+            return fault(Fault::ReservedOperand { pc, operand })
+        }
+        // Not used by the opcode.
+        _ => 0,
+    };
+    match instruction.opcode {
+        Opcode::Adv => Op::Adv(combo),
+        Opcode::Bxl => Op::Bxl(instruction.operand),
+        Opcode::Bst => Op::Bst(combo),
+        Opcode::Jnz => Op::Jnz(instruction.operand as usize),
+        Opcode::Bxc => Op::Bxc,
+        Opcode::Out => Op::Out(combo),
+        Opcode::Bdv => Op::Bdv(combo),
+        Opcode::Cdv => Op::Cdv(combo),
+    }
+}
+
+/// `A` divided by 2 to the power of the combo operand, see [`BitComp`].
+fn divide(regs: &[Num; 7], combo: Combo) -> Num {
+    let shift = u32::try_from(regs[combo as usize]).unwrap_or(u32::MAX);
+    regs[A].checked_shr(shift).unwrap_or(0)
+}
+
+/// Output values packed as octal digits, the first output being the most significant digit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Octal {
+    digits: u64,
+    len: u32,
+}
+
+impl Octal {
+    /// Maximum number of digits that fit.
+    pub const CAPACITY: u32 = u64::BITS / 3;
+
+    /// Packs `values`, `None` if there are too many or one of them is not an octal digit.
+    pub fn from_digits(values: &[Num]) -> Option<Self> {
+        values
+            .iter()
+            .try_fold(Self::default(), |octal, value| octal.push(*value))
+            .ok()
+    }
+
+    fn push(self, value: Num) -> Result<Self, ()> {
+        match u64::try_from(value) {
+            Ok(digit @ 0..=7) if self.len < Self::CAPACITY => Ok(Self {
+                digits: (self.digits << 3) | digit,
+                len: self.len + 1,
+            }),
+            _ => Err(()),
+        }
+    }
+
+    pub fn digits(&self) -> u64 {
+        self.digits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Comma separated, like [`BitComp::run`].
+impl Display for Octal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = (0..self.len)
+            .rev()
+            .map(|idx| (self.digits >> (3 * idx)) & 7)
+            .join(",");
+        write!(f, "{digits}")
+    }
+}
+
+/// A [`BitComp`] program decoded once up front, for running it many times with different values
+/// of register `A`.
+///
+/// Every address is decoded, so that jumps to odd addresses behave like in the interpreter, and
+/// faults are only raised once the faulty address is executed.
+#[derive(Clone, Debug)]
+pub struct Compiled {
+    ops: Vec<Op>,
+    faults: Vec<Fault>,
+    reg_b: Num,
+    reg_c: Num,
+    max_cycles: Option<usize>,
+}
+
+impl Compiled {
+    /// Stops execution with [`Fault::CycleLimit`] after `max_cycles` instructions, `None` for no
+    /// limit.
+    pub fn set_max_cycles(&mut self, max_cycles: Option<usize>) {
+        self.max_cycles = max_cycles;
+    }
+
+    /// Runs the program from the start with register `A` set to `reg_a` and returns everything
+    /// it output.
+    pub fn output(&self, reg_a: Num) -> Result<Vec<Num>, Fault> {
+        let mut output = vec![];
+        self.execute(reg_a, |value| {
+            output.push(value);
+            ControlFlow::Continue(())
+        })?;
+        Ok(output)
+    }
+
+    /// Like [`Compiled::output`], but without allocating. `None` if the output does not fit into
+    /// an [`Octal`], in which case execution stops early.
+    pub fn packed(&self, reg_a: Num) -> Result<Option<Octal>, Fault> {
+        let mut packed = Ok(Octal::default());
+        self.execute(reg_a, |value| {
+            packed = packed.and_then(|octal| octal.push(value));
+            match packed {
+                Ok(_) => ControlFlow::Continue(()),
+                Err(()) => ControlFlow::Break(()),
+            }
+        })?;
+        Ok(packed.ok())
+    }
+
+    fn execute(
+        &self,
+        reg_a: Num,
+        mut out: impl FnMut(Num) -> ControlFlow<()>,
+    ) -> Result<(), Fault> {
+        let mut regs = [0, 1, 2, 3, reg_a, self.reg_b, self.reg_c];
+        let mut pc = 0;
+        let mut cycles = 0;
+        let max_cycles = self.max_cycles.unwrap_or(usize::MAX);
+        while let Some(op) = self.ops.get(pc) {
+            if cycles >= max_cycles {
+                return Err(Fault::CycleLimit { pc, cycles });
+            }
+            cycles += 1;
+            pc += 2;
+            match *op {
+                Op::Adv(operand) => regs[A] = divide(&regs, operand),
+                Op::Bxl(literal) => regs[B] ^= literal,
+                Op::Bst(operand) => regs[B] = regs[operand as usize] % 8,
+                Op::Jnz(target) => {
+                    if regs[A] != 0 {
+                        pc = target;
+                    }
+                }
+                Op::Bxc => regs[B] ^= regs[C],
+                Op::Out(operand) => {
+                    if out(regs[operand as usize] % 8).is_break() {
+                        return Ok(());
+                    }
+                }
+                Op::Bdv(operand) => regs[B] = divide(&regs, operand),
+                Op::Cdv(operand) => regs[C] = divide(&regs, operand),
+                Op::Fault(idx) => return Err(self.faults[idx]),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BitComp {
+    /// Decodes the program, keeping the current values of registers `B` and `C` as their initial
+    /// values. The compiled program always runs from the start.
+    pub fn compile(&self) -> Compiled {
+        let memory = self.get_mem();
+        let mut faults = vec![];
+        Compiled {
+            ops: (0..memory.len())
+                .map(|pc| decode(&memory, pc, &mut faults))
+                .collect(),
+            faults,
+            reg_b: self.register(Register::B),
+            reg_c: self.register(Register::C),
+            max_cycles: self.max_cycles(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same(memory: Vec<Num>, b: Num) {
+        let mut computer = BitComp::new(0, b, 0, memory);
+        computer.set_max_cycles(Some(1000));
+        let compiled = computer.compile();
+        for a in 0..512 {
+            let mut interpreted = computer.clone();
+            interpreted.set_a(a);
+            let expected = interpreted.output();
+            assert_eq!(expected, compiled.output(a), "A = {a}");
+            assert_eq!(
+                expected.map(|output| Octal::from_digits(&output)),
+                compiled.packed(a),
+                "A = {a}"
+            );
+        }
+    }
+
+    #[test]
+    fn matches_interpreter() {
+        assert_same(vec![2, 4, 1, 5, 7, 5, 4, 3, 1, 6, 0, 3, 5, 5, 3, 0], 0);
+        assert_same(vec![0, 1, 5, 4, 3, 0], 0);
+        // Jumps into an operand.
+        assert_same(vec![3, 3, 2, 5, 4, 5, 4, 0, 3, 1], 9);
+        // Faults for some values of A only.
+        assert_same(vec![2, 4, 3, 6, 5, 4, 3, 0, 5, 7], 0);
+        assert_same(vec![0, 1, 3, 5, 9, 0, 3, 0], 0);
+        // Does not halt.
+        assert_same(vec![1, 1, 3, 0], 0);
+        assert_same(vec![5, 4, 0, 5], 70);
+    }
+
+    #[test]
+    fn packs_output() {
+        let octal = Octal::from_digits(&[0, 4, 6, 3]).unwrap();
+        assert_eq!(0o0463, octal.digits());
+        assert_eq!("0,4,6,3", octal.to_string());
+        assert_ne!(Octal::from_digits(&[4, 6, 3]), Some(octal));
+        assert_eq!(None, Octal::from_digits(&[8]));
+        assert_eq!(None, Octal::from_digits(&[0; 22]));
+
+        let compiled = BitComp::assemble("out A\njnz 0").unwrap().compile();
+        assert_eq!(Ok(None), compiled.packed(1));
+        assert_eq!(
+            Ok(Some(Octal::from_digits(&[0]).unwrap())),
+            compiled.packed(0)
+        );
+    }
+}
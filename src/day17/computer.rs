@@ -0,0 +1,381 @@
+use std::{fmt::Display, str::FromStr};
+
+use itertools::Itertools;
+
+use crate::error::CaptureError;
+use crate::utils::Separated;
+
+use super::{asm::Instruction, Num};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Register {
+    A,
+    B,
+    C,
+}
+
+impl Register {
+    pub const ALL: [Self; 3] = [Self::A, Self::B, Self::C];
+}
+
+/// An executed instruction and its effects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub pc: usize,
+    pub instruction: Instruction,
+    /// Changed registers with their old and new value.
+    pub changes: Vec<(Register, Num, Num)>,
+    pub output: Option<Num>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:>3}: {}", self.pc, self.instruction)?;
+        for (reg, old, new) in &self.changes {
+            write!(f, " {reg:?}: {old} -> {new}")?;
+        }
+        if let Some(output) = self.output {
+            write!(f, " out: {output}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl Opcode {
+    /// Whether the operand is a combo operand, and not a literal.
+    pub fn has_combo_operand(self) -> bool {
+        matches!(
+            self,
+            Opcode::Adv | Opcode::Bst | Opcode::Out | Opcode::Bdv | Opcode::Cdv
+        )
+    }
+}
+
+/// Returns the invalid opcode as error.
+impl TryFrom<Num> for Opcode {
+    type Error = Num;
+
+    fn try_from(value: Num) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Opcode::Adv),
+            1 => Ok(Opcode::Bxl),
+            2 => Ok(Opcode::Bst),
+            3 => Ok(Opcode::Jnz),
+            4 => Ok(Opcode::Bxc),
+            5 => Ok(Opcode::Out),
+            6 => Ok(Opcode::Bdv),
+            7 => Ok(Opcode::Cdv),
+            op => Err(op),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitComp {
+    reg_a: Num,
+    reg_b: Num,
+    reg_c: Num,
+    out_reg: Vec<Num>,
+    pc: usize,
+    memory: Vec<Num>,
+    cycles: usize,
+    max_cycles: Option<usize>,
+}
+
+/// Reason a program stopped before halting regularly, at instruction `pc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fault {
+    IllegalOpcode {
+        pc: usize,
+        opcode: Num,
+    },
+    /// Combo operand 7 or larger.
+    ReservedOperand {
+        pc: usize,
+        operand: Num,
+    },
+    /// Opcode at the end of the program without an operand.
+    TruncatedInstruction {
+        pc: usize,
+    },
+    CycleLimit {
+        pc: usize,
+        cycles: usize,
+    },
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fault::IllegalOpcode { pc, opcode } => write!(f, "Illegal opcode {opcode} at {pc}"),
+            Fault::ReservedOperand { pc, operand } => {
+                write!(f, "Reserved combo operand {operand} at {pc}")
+            }
+            Fault::TruncatedInstruction { pc } => write!(f, "Truncated instruction at {pc}"),
+            Fault::CycleLimit { pc, cycles } => write!(
+                f,
+                "Program did not halt within {cycles} cycles, stopped at {pc}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Fault {}
+
+impl BitComp {
+    pub fn new(reg_a: Num, reg_b: Num, reg_c: Num, memory: Vec<Num>) -> Self {
+        Self {
+            reg_a,
+            reg_b,
+            reg_c,
+            out_reg: vec![],
+            pc: 0,
+            memory,
+            cycles: 0,
+            max_cycles: None,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<String, Fault> {
+        Ok(self.output()?.iter().map(|n| n.to_string()).join(","))
+    }
+
+    /// Runs the program until it halts and returns everything it output.
+    pub fn output(&mut self) -> Result<Vec<Num>, Fault> {
+        while self.pc < self.memory.len() {
+            self.execute()?;
+        }
+        Ok(std::mem::take(&mut self.out_reg))
+    }
+
+    /// Stops execution with [`Fault::CycleLimit`] after `max_cycles` instructions, `None` for no
+    /// limit.
+    pub fn set_max_cycles(&mut self, max_cycles: Option<usize>) {
+        self.max_cycles = max_cycles;
+    }
+
+    pub fn max_cycles(&self) -> Option<usize> {
+        self.max_cycles
+    }
+
+    /// Number of instructions executed so far.
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    fn execute(&mut self) -> Result<(), Fault> {
+        if self.max_cycles.is_some_and(|max| self.cycles >= max) {
+            return Err(Fault::CycleLimit {
+                pc: self.pc,
+                cycles: self.cycles,
+            });
+        }
+        let instruction = Instruction::decode(&self.memory, self.pc)?;
+        let addr = self.pc + 1;
+        match instruction.opcode {
+            Opcode::Adv => self.adv(addr)?,
+            Opcode::Bxl => self.bxl(addr),
+            Opcode::Bst => self.bst(addr)?,
+            Opcode::Jnz => self.jnz(addr),
+            Opcode::Bxc => self.bxc(addr),
+            Opcode::Out => self.out(addr)?,
+            Opcode::Bdv => self.bdv(addr)?,
+            Opcode::Cdv => self.cdv(addr)?,
+        }
+        self.cycles += 1;
+        Ok(())
+    }
+
+    /// Executes the instruction at `pc`, returns `None` if the program already halted.
+    pub fn step(&mut self) -> Result<Option<Step>, Fault> {
+        let pc = self.pc;
+        if pc >= self.memory.len() {
+            return Ok(None);
+        }
+        let before = Register::ALL.map(|reg| self.register(reg));
+        let out_len = self.out_reg.len();
+        self.execute()?;
+        Ok(Some(Step {
+            pc,
+            instruction: Instruction::decode(&self.memory, pc)?,
+            changes: Register::ALL
+                .into_iter()
+                .zip(before)
+                .filter(|(reg, old)| self.register(*reg) != *old)
+                .map(|(reg, old)| (reg, old, self.register(reg)))
+                .collect(),
+            output: self.out_reg.get(out_len).copied(),
+        }))
+    }
+
+    pub fn register(&self, register: Register) -> Num {
+        match register {
+            Register::A => self.reg_a,
+            Register::B => self.reg_b,
+            Register::C => self.reg_c,
+        }
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Values output since the last call of [`BitComp::output`].
+    pub fn out_reg(&self) -> &[Num] {
+        &self.out_reg
+    }
+
+    pub fn set_a(&mut self, new_val: Num) {
+        self.reg_a = new_val;
+    }
+
+    pub fn get_mem(&self) -> Vec<Num> {
+        self.memory.clone()
+    }
+
+    fn combo_operand(&self, addr: usize) -> Result<Num, Fault> {
+        match self.memory[addr] {
+            4 => Ok(self.reg_a),
+            5 => Ok(self.reg_b),
+            6 => Ok(self.reg_c),
+            n if (0..=3).contains(&n) => Ok(n),
+            operand => Err(Fault::ReservedOperand {
+                pc: self.pc,
+                operand,
+            }),
+        }
+    }
+
+    /// `A` divided by 2 to the power of the combo operand, which is 0 for shifts by 64 bits or
+    /// more.
+    fn divide(&self, addr: usize) -> Result<Num, Fault> {
+        let shift = u32::try_from(self.combo_operand(addr)?).unwrap_or(u32::MAX);
+        Ok(self.reg_a.checked_shr(shift).unwrap_or(0))
+    }
+
+    fn adv(&mut self, addr: usize) -> Result<(), Fault> {
+        self.reg_a = self.divide(addr)?;
+        self.pc += 2;
+        Ok(())
+    }
+
+    fn bxl(&mut self, addr: usize) {
+        self.reg_b ^= self.memory[addr];
+        self.pc += 2;
+    }
+
+    fn bst(&mut self, addr: usize) -> Result<(), Fault> {
+        self.reg_b = self.combo_operand(addr)? % 8;
+        self.pc += 2;
+        Ok(())
+    }
+
+    fn jnz(&mut self, addr: usize) {
+        if self.reg_a != 0 {
+            self.pc = self.memory[addr] as usize;
+        } else {
+            self.pc += 2;
+        }
+    }
+
+    fn bxc(&mut self, _: usize) {
+        self.reg_b ^= self.reg_c;
+        self.pc += 2;
+    }
+
+    fn out(&mut self, addr: usize) -> Result<(), Fault> {
+        self.out_reg.push(self.combo_operand(addr)? % 8);
+        self.pc += 2;
+        Ok(())
+    }
+
+    fn bdv(&mut self, addr: usize) -> Result<(), Fault> {
+        self.reg_b = self.divide(addr)?;
+        self.pc += 2;
+        Ok(())
+    }
+
+    fn cdv(&mut self, addr: usize) -> Result<(), Fault> {
+        self.reg_c = self.divide(addr)?;
+        self.pc += 2;
+        Ok(())
+    }
+}
+
+impl Display for BitComp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "A: {}
+B: {}
+C: {}
+Mem: {:?}",
+            self.reg_a, self.reg_b, self.reg_c, self.memory
+        )
+    }
+}
+
+impl FromStr for BitComp {
+    type Err = CaptureError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let caps = regex_parse!(
+            r"Register A: (?<reg_a>\d+)\nRegister B: (?<reg_b>\d+)\nRegister C: (?<reg_c>\d+)\n\nProgram: (?<prog>(:?\d+,?)*)",
+            s,
+            { reg_a: Num, reg_b: Num, reg_c: Num, prog: Separated<Num> }
+        )?;
+        Ok(Self::new(caps.reg_a, caps.reg_b, caps.reg_c, caps.prog.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(memory: Vec<Num>) -> Result<Vec<Num>, Fault> {
+        BitComp::new(0, 0, 0, memory).output()
+    }
+
+    #[test]
+    fn reports_faults() {
+        assert_eq!(
+            Err(Fault::IllegalOpcode { pc: 2, opcode: 9 }),
+            output(vec![1, 1, 9, 0])
+        );
+        assert_eq!(
+            Err(Fault::ReservedOperand { pc: 0, operand: 7 }),
+            output(vec![5, 7])
+        );
+        assert_eq!(
+            Err(Fault::TruncatedInstruction { pc: 2 }),
+            output(vec![0, 1, 5])
+        );
+        assert_eq!(Ok(vec![]), output(vec![3, 9]));
+
+        let mut computer = BitComp::new(1, 0, 0, vec![1, 1, 3, 0]);
+        computer.set_max_cycles(Some(10));
+        assert_eq!(
+            Err(Fault::CycleLimit { pc: 0, cycles: 10 }),
+            computer.output()
+        );
+    }
+
+    #[test]
+    fn shifts_out_all_bits() {
+        // adv B with B = 70
+        let mut computer = BitComp::new(5, 70, 0, vec![0, 5, 5, 4]);
+        assert_eq!(Ok(vec![0]), computer.output());
+    }
+}
@@ -0,0 +1,180 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use super::{
+    computer::{BitComp, Fault, Register, Step},
+    Num,
+};
+
+/// Cycles a [`Debugger`] runs before giving up, unless configured otherwise.
+pub const DEFAULT_MAX_CYCLES: usize = 1_000_000;
+
+/// Why [`Debugger::run`] stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    /// The instruction at the breakpoint is about to be executed.
+    Breakpoint(usize),
+}
+
+/// State after one cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: usize,
+    /// Address of the executed instruction.
+    pub pc: usize,
+    pub reg_a: Num,
+    pub reg_b: Num,
+    pub reg_c: Num,
+    pub out: Option<Num>,
+}
+
+impl Display for TraceEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>6} pc={:<3} A={} B={} C={}",
+            self.cycle, self.pc, self.reg_a, self.reg_b, self.reg_c
+        )?;
+        if let Some(out) = self.out {
+            write!(f, " out={out}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a [`BitComp`] step by step, with breakpoints, a cycle budget and an optional trace.
+#[derive(Clone, Debug)]
+pub struct Debugger {
+    computer: BitComp,
+    breakpoints: BTreeSet<usize>,
+    trace: Option<Vec<TraceEntry>>,
+}
+
+impl Debugger {
+    pub fn new(mut computer: BitComp) -> Self {
+        computer.set_max_cycles(Some(DEFAULT_MAX_CYCLES));
+        Self {
+            computer,
+            breakpoints: BTreeSet::new(),
+            trace: None,
+        }
+    }
+
+    pub fn with_max_cycles(mut self, max_cycles: usize) -> Self {
+        self.computer.set_max_cycles(Some(max_cycles));
+        self
+    }
+
+    /// Records a [`TraceEntry`] for every cycle.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    pub fn computer(&self) -> &BitComp {
+        &self.computer
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.computer.cycles()
+    }
+
+    /// Executes one instruction, `None` if the program already halted.
+    pub fn step(&mut self) -> Result<Option<Step>, Fault> {
+        let Some(step) = self.computer.step()? else {
+            return Ok(None);
+        };
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                cycle: self.computer.cycles(),
+                pc: step.pc,
+                reg_a: self.computer.register(Register::A),
+                reg_b: self.computer.register(Register::B),
+                reg_c: self.computer.register(Register::C),
+                out: step.output,
+            });
+        }
+        Ok(Some(step))
+    }
+
+    /// Runs until the program halts or reaches a breakpoint. Always executes at least one
+    /// instruction, so calling it again continues from a breakpoint.
+    pub fn run(&mut self) -> Result<Stop, Fault> {
+        while self.step()?.is_some() {
+            if self.breakpoints.contains(&self.computer.pc()) {
+                return Ok(Stop::Breakpoint(self.computer.pc()));
+            }
+        }
+        Ok(Stop::Halted)
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// The trace, one cycle per line.
+    pub fn trace_log(&self) -> String {
+        self.trace()
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer() -> BitComp {
+        let mut computer = BitComp::assemble("bst A\nout B\nadv 3\njnz 0").unwrap();
+        computer.set_a(0o21);
+        computer
+    }
+
+    #[test]
+    fn steps_through_program() {
+        let mut debugger = Debugger::new(computer());
+        let step = debugger.step().unwrap().unwrap();
+        assert_eq!("  0: bst A B: 0 -> 1", step.to_string());
+        assert_eq!(Some(1), debugger.step().unwrap().unwrap().output);
+
+        debugger.add_breakpoint(2);
+        assert_eq!(Ok(Stop::Breakpoint(2)), debugger.run());
+        assert_eq!(&[1], debugger.computer().out_reg());
+        debugger.remove_breakpoint(2);
+        assert_eq!(Ok(Stop::Halted), debugger.run());
+        assert_eq!(&[1, 2], debugger.computer().out_reg());
+        assert_eq!(Ok(None), debugger.step());
+        assert_eq!(8, debugger.cycles());
+    }
+
+    #[test]
+    fn stops_at_cycle_limit() {
+        let mut computer = BitComp::assemble("bxl 1\njnz 0").unwrap();
+        computer.set_a(1);
+        let mut debugger = Debugger::new(computer).with_max_cycles(5);
+        assert_eq!(Err(Fault::CycleLimit { pc: 2, cycles: 5 }), debugger.run());
+    }
+
+    #[test]
+    fn records_trace() {
+        let mut debugger = Debugger::new(computer()).with_trace();
+        debugger.run().unwrap();
+        assert_eq!(8, debugger.trace().len());
+        assert_eq!(
+            vec![
+                "     1 pc=0   A=17 B=1 C=0",
+                "     2 pc=2   A=17 B=1 C=0 out=1"
+            ],
+            debugger.trace_log().lines().take(2).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::{bail, Context, Result};
+
+use super::{
+    asm::Instruction,
+    compiled::{Compiled, Octal},
+    computer::{BitComp, Fault, Opcode},
+    Num,
+};
+
+/// Checks that `program` is a single loop ending in `jnz 0`, which shifts `A` by 3 bits and
+/// outputs one value per iteration, and which sets `B` and `C` before reading them. Then each
+/// output only depends on the bits of `A` not yet shifted out.
+fn check_shape(program: &[Num]) -> Result<()> {
+    if !program.len().is_multiple_of(2) {
+        bail!("Program has an odd length of {}", program.len());
+    }
+    if program.len() * 3 >= Num::BITS as usize {
+        bail!(
+            "Program of length {} needs more than {} bits for A",
+            program.len(),
+            Num::BITS
+        );
+    }
+    let instructions = (0..program.len())
+        .step_by(2)
+        .map(|pc| {
+            Instruction::decode(program, pc)
+                .map(|instruction| (instruction.opcode, instruction.operand))
+        })
+        .collect::<Result<Vec<_>, Fault>>()?;
+
+    let Some(((Opcode::Jnz, 0), body)) = instructions.split_last() else {
+        bail!("Program does not end with `jnz 0`");
+    };
+    let count = |opcode: Opcode| body.iter().filter(|(op, _)| *op == opcode).count();
+    if count(Opcode::Jnz) > 0 {
+        bail!("Program jumps inside of its loop");
+    }
+    if count(Opcode::Adv) != 1 || !body.contains(&(Opcode::Adv, 3)) {
+        bail!("Loop does not shift A by 3 bits exactly once");
+    }
+    if count(Opcode::Out) != 1 {
+        bail!("Loop does not output exactly once");
+    }
+
+    let (mut b_set, mut c_set) = (false, false);
+    for (idx, (opcode, operand)) in body.iter().enumerate() {
+        let combo = opcode.has_combo_operand().then_some(*operand);
+        if combo == Some(7) {
+            bail!("Instruction {} uses the reserved operand 7", idx * 2);
+        }
+        let reads_b = matches!(opcode, Opcode::Bxl | Opcode::Bxc) || combo == Some(5);
+        let reads_c = *opcode == Opcode::Bxc || combo == Some(6);
+        if (reads_b && !b_set) || (reads_c && !c_set) {
+            bail!(
+                "Instruction {} reads {} before the loop sets it",
+                idx * 2,
+                if reads_b && !b_set { 'B' } else { 'C' }
+            );
+        }
+        match opcode {
+            Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => b_set = true,
+            Opcode::Cdv => c_set = true,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Extends `prefix` by one octal digit at a time, such that the program outputs the last
+/// `program.len() - remaining` values of itself.
+fn search(compiled: &Compiled, program: &[Num], prefix: Num, remaining: usize) -> Option<Num> {
+    if remaining == 0 {
+        return Some(prefix);
+    }
+    let expected = Octal::from_digits(&program[remaining - 1..]);
+    (0..8)
+        .map(|digit| (prefix << 3) | digit)
+        .filter(|a| *a != 0)
+        .find_map(|a| {
+            if compiled.packed(a).is_ok_and(|output| output == expected) {
+                search(compiled, program, a, remaining - 1)
+            } else {
+                None
+            }
+        })
+}
+
+/// Smallest positive value of register `A` for which `computer` outputs its own program.
+///
+/// Works for programs of the shape checked by [`check_shape`]: As every iteration consumes the
+/// lowest 3 bits of `A`, the last output only depends on the highest octal digit of `A`, the
+/// second to last on the two highest and so on. So `A` is built from the highest digit down,
+/// backtracking if no digit produces the next output.
+pub fn find_quine(computer: &BitComp) -> Result<Num> {
+    let program = computer.get_mem();
+    check_shape(&program)?;
+    search(&computer.compile(), &program, 0, program.len())
+        .context("No value of A makes the program output itself")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(program: &str) -> BitComp {
+        format!("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {program}")
+            .parse()
+            .unwrap()
+    }
+
+    fn assert_quine(program: &str) -> Num {
+        let a = find_quine(&computer(program)).unwrap();
+        let mut quine = computer(program);
+        quine.set_a(a);
+        assert_eq!(program, quine.run().unwrap());
+        a
+    }
+
+    #[test]
+    fn finds_quines() {
+        assert_eq!(117440, assert_quine("0,3,5,4,3,0"));
+        assert_quine("2,4,1,5,7,5,4,3,1,6,0,3,5,5,3,0");
+        assert_quine("2,4,1,3,7,5,0,3,1,5,4,4,5,5,3,0");
+        assert_quine("2,4,1,1,7,5,1,5,4,3,5,5,0,3,3,0");
+    }
+
+    #[test]
+    fn rejects_other_shapes() {
+        let error = |program| find_quine(&computer(program)).unwrap_err().to_string();
+        assert_eq!(
+            "Loop does not shift A by 3 bits exactly once",
+            error("0,1,5,4,3,0")
+        );
+        assert_eq!("Program does not end with `jnz 0`", error("0,3,5,4"));
+        assert_eq!(
+            "Instruction 0 reads B before the loop sets it",
+            error("1,2,0,3,5,5,3,0")
+        );
+        assert_eq!("Program has an odd length of 3", error("0,3,5"));
+        assert_eq!(
+            "Loop does not output exactly once",
+            error("0,3,5,4,5,4,3,0")
+        );
+        assert_eq!(
+            "No value of A makes the program output itself",
+            error("0,3,5,1,3,0")
+        );
+    }
+}
@@ -1,23 +1,30 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 use pathfinding::{grid::Grid, prelude::dijkstra};
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Output2 = String;
 type Input = (Vec<(usize, usize)>, (usize, usize), usize);
 
 #[aoc_generator(day18)]
-pub fn input_generator(input: &str) -> Input {
-    (
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(18, input);
+    Ok((
         input
             .lines()
-            .map(|line| line.split_once(',').unwrap())
-            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-            .collect(),
+            .map(|line| {
+                let (x, y) = ctx.split_once(line, ",")?;
+                Ok((ctx.parse(x)?, ctx.parse(y)?))
+            })
+            .collect::<Result<Vec<(usize, usize)>, ParseError>>()?,
         (70, 70),
         1024,
-    )
+    ))
 }
 
 fn create_ram(bytes: &[(usize, usize)], end: (usize, usize), fallen: usize) -> Grid {
@@ -59,12 +66,35 @@ pub fn solve_part2(input: &Input) -> Output2 {
     format!("{},{}", bytes[idx - 1].0, bytes[idx - 1].1)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: usize = 18;
+    const TITLE: &'static str = "RAM Run";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output2;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -101,14 +131,17 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(22, solve_part1(&(input_generator(sample()).0, (6, 6), 12)));
+        assert_eq!(
+            22,
+            solve_part1(&(input_generator(sample()).unwrap().0, (6, 6), 12))
+        );
     }
 
     #[test]
     fn samples_part2() {
         assert_eq!(
             "6,1",
-            solve_part2(&(input_generator(sample()).0, (6, 6), 12))
+            solve_part2(&(input_generator(sample()).unwrap().0, (6, 6), 12))
         );
     }
 }
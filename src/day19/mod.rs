@@ -1,3 +1,4 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
@@ -5,12 +6,16 @@ use itertools::Itertools;
 use cached::proc_macro::cached;
 use cached::SizedCache;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Input = (Vec<String>, Vec<String>);
 
 #[aoc_generator(day19)]
-pub fn input_generator(input: &str) -> Input {
-    let (patterns, designs) = input.split_once("\n\n").unwrap();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(19, input);
+    let (patterns, designs) = ctx.split_once(input, "\n\n")?;
 
     let patterns = patterns
         .split(", ")
@@ -20,7 +25,7 @@ pub fn input_generator(input: &str) -> Input {
         .lines()
         .map(|des| des.to_string())
         .collect::<Vec<_>>();
-    (patterns, designs)
+    Ok((patterns, designs))
 }
 
 #[cached(
@@ -64,12 +69,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: usize = 19;
+    const TITLE: &'static str = "Linen Layout";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -91,11 +119,11 @@ bbrgwb"
 
     #[test]
     fn samples_part1() {
-        assert_eq!(6, solve_part1(&input_generator(sample())));
+        assert_eq!(6, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(16, solve_part2(&input_generator(sample())));
+        assert_eq!(16, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
@@ -0,0 +1,183 @@
+use std::ops::RangeInclusive;
+
+/// Checks whether reports are safe, after removing up to a number of bad levels.
+///
+/// A report is safe if its levels are strictly increasing or strictly decreasing, with every
+/// step between neighbouring levels in the configured bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dampener {
+    tolerance: usize,
+    steps: RangeInclusive<i64>,
+}
+
+impl Default for Dampener {
+    fn default() -> Self {
+        Self {
+            tolerance: 0,
+            steps: 1..=3,
+        }
+    }
+}
+
+impl Dampener {
+    /// A dampener not removing any levels, allowing steps from 1 to 3.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows removing up to `tolerance` levels.
+    pub fn with_tolerance(mut self, tolerance: usize) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Bounds of the size of the steps between neighbouring levels.
+    pub fn with_steps(mut self, steps: RangeInclusive<i64>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn steps(&self) -> &RangeInclusive<i64> {
+        &self.steps
+    }
+
+    /// Whether `report` is safe after removing at most the tolerance. Reports with a single level
+    /// have no steps and are safe, empty reports are never safe.
+    pub fn is_safe(&self, report: &[i64]) -> bool {
+        self.dampen(report).is_some()
+    }
+
+    /// Indices of the fewest levels to remove to make `report` safe, ascending. `None` if more
+    /// than the tolerance would have to be removed, or if `report` is empty.
+    pub fn dampen(&self, report: &[i64]) -> Option<Vec<usize>> {
+        [1, -1]
+            .into_iter()
+            .filter_map(|direction| self.dampen_towards(report, direction))
+            .min_by_key(Vec::len)
+    }
+
+    /// Like [`Dampener::dampen`], but only for steps going up for `direction` 1 or down for -1.
+    ///
+    /// Dynamic programming over the level kept last: `kept[idx][removed]` is set if level `idx`
+    /// can be kept with `removed` levels removed before it, and holds the level kept before.
+    /// Only the `tolerance + 1` levels in front of a level can be kept before it, which makes
+    /// this linear in the length of the report.
+    fn dampen_towards(&self, report: &[i64], direction: i64) -> Option<Vec<usize>> {
+        if report.is_empty() {
+            return None;
+        }
+        let (len, tolerance) = (report.len(), self.tolerance);
+        let mut kept = vec![vec![None; tolerance + 1]; len];
+        for idx in 0..len {
+            if idx <= tolerance {
+                kept[idx][idx] = Some(None);
+            }
+            for prev in idx.saturating_sub(tolerance + 1)..idx {
+                let gap = idx - prev - 1;
+                if !self
+                    .steps
+                    .contains(&((report[idx] - report[prev]) * direction))
+                {
+                    continue;
+                }
+                for removed in 0..=tolerance - gap {
+                    if kept[prev][removed].is_some() && kept[idx][removed + gap].is_none() {
+                        kept[idx][removed + gap] = Some(Some(prev));
+                    }
+                }
+            }
+        }
+
+        // Levels after the last one kept are removed as well
+        let (mut idx, mut removed) = (len.saturating_sub(tolerance + 1)..len)
+            .flat_map(|idx| (0..=tolerance).map(move |removed| (idx, removed)))
+            .filter(|(idx, removed)| {
+                kept[*idx][*removed].is_some() && removed + len - 1 - idx <= tolerance
+            })
+            .min_by_key(|(idx, removed)| removed + len - 1 - idx)?;
+        let mut dropped = ((idx + 1)..len).rev().collect::<Vec<_>>();
+        while let Some(Some(prev)) = kept[idx][removed] {
+            dropped.extend((prev + 1..idx).rev());
+            removed -= idx - prev - 1;
+            idx = prev;
+        }
+        dropped.extend((0..idx).rev());
+        dropped.reverse();
+        Some(dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    /// Steps all from 1 to 3 up or all from 1 to 3 down, checked without the dampener.
+    fn is_safe(report: &[i64]) -> bool {
+        let steps = report
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect_vec();
+        steps.iter().all(|step| (1..=3).contains(step))
+            || steps.iter().all(|step| (-3..=-1).contains(step))
+    }
+
+    /// Fewest levels to remove to make `report` safe, trying every combination of up to
+    /// `tolerance` levels.
+    fn brute_force(report: &[i64], tolerance: usize) -> Option<usize> {
+        (0..=tolerance.min(report.len())).find(|count| {
+            (0..report.len()).combinations(*count).any(|removed| {
+                let rest = (0..report.len())
+                    .filter(|idx| !removed.contains(idx))
+                    .map(|idx| report[idx])
+                    .collect_vec();
+                is_safe(&rest)
+            })
+        })
+    }
+
+    #[test]
+    fn removes_levels() {
+        let dampener = Dampener::new().with_tolerance(2);
+        assert_eq!(Some(vec![]), dampener.dampen(&[1, 3, 6, 7, 9]));
+        assert_eq!(Some(vec![2]), dampener.dampen(&[1, 3, 2, 4, 5]));
+        assert_eq!(Some(vec![2, 3]), dampener.dampen(&[9, 7, 1, 2, 6, 5]));
+        assert_eq!(None, dampener.dampen(&[1, 9, 2, 8, 3, 7, 4]));
+        assert_eq!(Some(vec![1, 2]), dampener.dampen(&[5, 5, 1]));
+        assert_eq!(None, dampener.dampen(&[]));
+        assert!(!Dampener::new().is_safe(&[]));
+        assert_eq!(Some(vec![]), dampener.dampen(&[4]));
+        assert_eq!(
+            Some(vec![2]),
+            Dampener::new()
+                .with_tolerance(1)
+                .with_steps(2..=5)
+                .dampen(&[1, 5, 6, 10])
+        );
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let reports = [
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![9, 7, 6, 2, 1],
+            vec![1, 3, 2, 4, 5],
+            vec![8, 6, 4, 4, 1],
+            vec![1, 3, 6, 7, 9],
+            vec![4, 1, 2, 3, 9, 4, 5, 5, 6],
+            vec![3, 3, 3, 3],
+        ];
+        for tolerance in 0..4 {
+            let dampener = Dampener::new().with_tolerance(tolerance);
+            for report in &reports {
+                assert_eq!(
+                    brute_force(report, tolerance),
+                    dampener.dampen(report).map(|removed| removed.len()),
+                    "{report:?} with tolerance {tolerance}"
+                );
+            }
+        }
+    }
+}
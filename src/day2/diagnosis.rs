@@ -0,0 +1,247 @@
+use std::fmt::Display;
+
+use super::Dampener;
+
+/// Why a report is safe or not, see [`Dampener::diagnose`].
+///
+/// Indices are the ones of the level ending the first bad step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Diagnosis {
+    Safe,
+    /// Safe after removing the levels at these indices.
+    Dampened(Vec<usize>),
+    /// The step would be in bounds going the other way.
+    DirectionChange(usize),
+    StepTooLarge(usize),
+    /// The step is smaller than allowed, but not zero.
+    StepTooSmall(usize),
+    ZeroStep(usize),
+    /// The report has no levels.
+    Empty,
+}
+
+impl Diagnosis {
+    /// Names of the causes in the order of the variants, as used by [`Summary`].
+    pub const CAUSES: [&'static str; 7] = [
+        "safe",
+        "dampened",
+        "direction change",
+        "step too large",
+        "step too small",
+        "zero step",
+        "empty",
+    ];
+
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Diagnosis::Safe | Diagnosis::Dampened(_))
+    }
+
+    /// Index into [`Diagnosis::CAUSES`].
+    fn cause(&self) -> usize {
+        match self {
+            Diagnosis::Safe => 0,
+            Diagnosis::Dampened(_) => 1,
+            Diagnosis::DirectionChange(_) => 2,
+            Diagnosis::StepTooLarge(_) => 3,
+            Diagnosis::StepTooSmall(_) => 4,
+            Diagnosis::ZeroStep(_) => 5,
+            Diagnosis::Empty => 6,
+        }
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnosis::Safe => write!(f, "Safe"),
+            Diagnosis::Dampened(removed) => {
+                let removed = removed.iter().map(ToString::to_string).collect::<Vec<_>>();
+                let plural = if removed.len() == 1 { "" } else { "s" };
+                write!(
+                    f,
+                    "Safe after removing level{plural} {}",
+                    removed.join(", ")
+                )
+            }
+            Diagnosis::DirectionChange(idx) => write!(f, "Direction changes at level {idx}"),
+            Diagnosis::StepTooLarge(idx) => write!(f, "Step too large at level {idx}"),
+            Diagnosis::StepTooSmall(idx) => write!(f, "Step too small at level {idx}"),
+            Diagnosis::ZeroStep(idx) => write!(f, "No step at level {idx}"),
+            Diagnosis::Empty => write!(f, "No levels"),
+        }
+    }
+}
+
+impl Dampener {
+    /// Classifies `report`, with the first bad step if it can't be made safe.
+    pub fn diagnose(&self, report: &[i64]) -> Diagnosis {
+        match self.dampen(report) {
+            Some(removed) if removed.is_empty() => Diagnosis::Safe,
+            Some(removed) => Diagnosis::Dampened(removed),
+            None if report.is_empty() => Diagnosis::Empty,
+            None => self
+                .first_violation(report)
+                .expect("Reports that can't be made safe have a bad step"),
+        }
+    }
+
+    /// The first bad step of `report` without removing any levels, going up or down, whichever
+    /// gets further. `None` if the report is safe.
+    ///
+    /// Steps are bad by the same rule as for [`Dampener::dampen`].
+    fn first_violation(&self, report: &[i64]) -> Option<Diagnosis> {
+        let up = self.first_violation_towards(report, 1)?;
+        let down = self.first_violation_towards(report, -1)?;
+        Some(std::cmp::max_by_key(up, down, |(idx, _)| *idx).1)
+    }
+
+    /// Index and diagnosis of the first step not in bounds after multiplying it with
+    /// `direction`.
+    fn first_violation_towards(
+        &self,
+        report: &[i64],
+        direction: i64,
+    ) -> Option<(usize, Diagnosis)> {
+        let steps = self.steps();
+        report.windows(2).enumerate().find_map(|(idx, pair)| {
+            let (idx, delta) = (idx + 1, (pair[1] - pair[0]) * direction);
+            let diagnosis = if steps.contains(&delta) {
+                return None;
+            } else if steps.contains(&-delta) {
+                Diagnosis::DirectionChange(idx)
+            } else if delta == 0 {
+                Diagnosis::ZeroStep(idx)
+            } else if delta.abs() > *steps.end() {
+                Diagnosis::StepTooLarge(idx)
+            } else {
+                Diagnosis::StepTooSmall(idx)
+            };
+            Some((idx, diagnosis))
+        })
+    }
+
+    /// Counts the diagnoses of all `reports`.
+    pub fn summarize<'a>(&self, reports: impl IntoIterator<Item = &'a Vec<i64>>) -> Summary {
+        let mut counts = [0; Diagnosis::CAUSES.len()];
+        for report in reports {
+            counts[self.diagnose(report).cause()] += 1;
+        }
+        Summary { counts }
+    }
+}
+
+/// Number of reports per cause, printed as a table by its [`Display`] implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Summary {
+    counts: [usize; Diagnosis::CAUSES.len()],
+}
+
+impl Summary {
+    /// Reports per cause, in the order of [`Diagnosis::CAUSES`].
+    pub fn counts(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        Diagnosis::CAUSES.into_iter().zip(self.counts)
+    }
+
+    pub fn safe(&self) -> usize {
+        self.counts[0] + self.counts[1]
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = Diagnosis::CAUSES
+            .iter()
+            .map(|cause| cause.len())
+            .max()
+            .unwrap();
+        writeln!(f, "{:width$} | reports", "cause")?;
+        writeln!(f, "{:-<width$}-|--------", "")?;
+        for (cause, count) in self.counts() {
+            writeln!(f, "{cause:width$} | {count:7}")?;
+        }
+        write!(
+            f,
+            "{:width$} | {:7}",
+            "total",
+            self.counts.iter().sum::<usize>()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnoses_reports() {
+        let dampener = Dampener::new();
+        assert_eq!(Diagnosis::Safe, dampener.diagnose(&[7, 6, 4, 2, 1]));
+        assert_eq!(
+            Diagnosis::StepTooLarge(2),
+            dampener.diagnose(&[1, 2, 7, 8, 9])
+        );
+        assert_eq!(
+            Diagnosis::DirectionChange(2),
+            dampener.diagnose(&[1, 3, 2, 4, 5])
+        );
+        assert_eq!(Diagnosis::ZeroStep(3), dampener.diagnose(&[8, 6, 4, 4, 1]));
+        assert_eq!(
+            Diagnosis::StepTooSmall(1),
+            dampener.clone().with_steps(2..=3).diagnose(&[1, 2, 4])
+        );
+
+        assert_eq!(
+            Diagnosis::Safe,
+            dampener.clone().with_steps(0..=3).diagnose(&[1, 1, 2])
+        );
+        assert_eq!(
+            Diagnosis::Safe,
+            dampener.clone().with_steps(-1..=3).diagnose(&[1, 3, 2, 5])
+        );
+        assert_eq!(
+            Diagnosis::StepTooLarge(2),
+            dampener.clone().with_steps(-1..=3).diagnose(&[1, 3, 7])
+        );
+        assert_eq!(Diagnosis::Empty, dampener.diagnose(&[]));
+        assert_eq!(Diagnosis::Safe, dampener.diagnose(&[4]));
+
+        let dampener = Dampener::new().with_tolerance(1);
+        assert_eq!(
+            Diagnosis::Safe,
+            dampener.clone().with_steps(0..=3).diagnose(&[1, 1, 2])
+        );
+        assert_eq!(
+            "Safe after removing level 2",
+            dampener.diagnose(&[1, 3, 2, 4, 5]).to_string()
+        );
+        assert_eq!(
+            Diagnosis::StepTooLarge(2),
+            dampener.diagnose(&[1, 2, 7, 8, 9])
+        );
+    }
+
+    #[test]
+    fn counts_causes() {
+        let reports = [
+            vec![1, 2, 3],
+            vec![1, 5, 2],
+            vec![3, 3, 4],
+            vec![1, 3, 2, 4],
+            vec![],
+        ];
+        let summary = Dampener::new().summarize(&reports);
+        assert_eq!(
+            vec![
+                ("safe", 1),
+                ("dampened", 0),
+                ("direction change", 1),
+                ("step too large", 1),
+                ("step too small", 0),
+                ("zero step", 1),
+                ("empty", 1),
+            ],
+            summary.counts().collect::<Vec<_>>()
+        );
+        assert_eq!(1, summary.safe());
+    }
+}
@@ -1,36 +1,37 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
+mod dampener;
+mod diagnosis;
+
+pub use dampener::Dampener;
+pub use diagnosis::{Diagnosis, Summary};
+
 type Output = usize;
 type Input = Vec<Vec<i64>>;
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Result<Input> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(2, input);
     input
         .lines()
         .map(|line| {
             line.split_whitespace()
-                .map(|n| {
-                    n.parse::<i64>()
-                        .with_context(|| format!("Not a number: {}", n))
-                })
+                .map(|n| ctx.parse::<i64>(n))
                 .collect()
         })
-        .collect::<Result<Vec<Vec<i64>>>>()
+        .collect::<Result<Vec<Vec<i64>>, ParseError>>()
 }
 
+/// Whether all steps of `report` go in the same direction and are between 1 and 3. Empty
+/// reports are not safe.
 pub fn is_safe(report: &[i64]) -> bool {
-    let safe_pairs = report
-        .windows(2)
-        .map(|pair| pair[0] - pair[1])
-        .filter(|delta| delta.abs() <= 3)
-        .map(|delta| delta.signum())
-        .sum::<i64>()
-        .abs();
-
-    safe_pairs == report.len() as i64 - 1
+    Dampener::new().is_safe(report)
 }
 
 #[aoc(day2, part1)]
@@ -38,28 +39,9 @@ pub fn solve_part1(input: &Input) -> Result<Output> {
     Ok(input.iter().filter(|report| is_safe(report)).count())
 }
 
+/// Whether `report` is safe after removing at most one level.
 pub fn is_safe_dampened(report: &[i64]) -> bool {
-    for idx in 0..report.len() {
-        if is_safe2(report, idx) {
-            return true;
-        }
-    }
-    false
-}
-
-pub fn is_safe2(report: &[i64], idx: usize) -> bool {
-    let safe_pairs = report
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| *i != idx)
-        .tuple_windows()
-        .map(|((_, a), (_, b))| a - b)
-        .filter(|delta| delta.abs() <= 3)
-        .map(|delta| delta.signum())
-        .sum::<i64>()
-        .abs();
-
-    safe_pairs == report.len() as i64 - 2
+    Dampener::new().with_tolerance(1).is_safe(report)
 }
 
 #[aoc(day2, part2)]
@@ -70,12 +52,35 @@ pub fn solve_part2(input: &Input) -> Result<Output> {
         .count())
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input).unwrap()).unwrap()
+pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
+    solve_part1(&input_generator(input)?)
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input).unwrap()).unwrap()
+pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
+    solve_part2(&input_generator(input)?)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: usize = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        solve_part1(input)
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        solve_part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -101,4 +106,33 @@ mod tests {
     fn samples_part2() {
         assert_eq!(4, solve_part2(&input_generator(sample()).unwrap()).unwrap());
     }
+
+    #[test]
+    fn blank_reports_are_unsafe() {
+        let input = input_generator(&format!("{}\n1 2 3\n", sample())).unwrap();
+        assert_eq!(Vec::<i64>::new(), input[6]);
+        assert_eq!(3, solve_part1(&input).unwrap());
+        assert_eq!(5, solve_part2(&input).unwrap());
+    }
+
+    #[test]
+    fn summarizes_sample() {
+        let summary = Dampener::new()
+            .with_tolerance(1)
+            .summarize(&input_generator(sample()).unwrap());
+        assert_eq!(4, summary.safe());
+        assert_eq!(
+            "cause            | reports
+-----------------|--------
+safe             |       2
+dampened         |       2
+direction change |       0
+step too large   |       2
+step too small   |       0
+zero step        |       0
+empty            |       0
+total            |       6",
+            summary.to_string()
+        );
+    }
 }
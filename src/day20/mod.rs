@@ -1,21 +1,30 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 use pathfinding::{grid::Grid, prelude::dijkstra_all};
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Pos = (usize, usize);
 type Input = (Grid, Pos, usize);
 
 #[aoc_generator(day20)]
-pub fn input_generator(input: &str) -> Input {
-    let start_index = input
-        .chars()
-        .filter(|c| *c != '\n')
-        .position(|c| c == 'S')
-        .unwrap();
-
-    let x_size = input.lines().next().unwrap().len();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(20, input);
+    ctx.check_rectangular(input)?;
+    let find_tile = |tile: char| {
+        input
+            .chars()
+            .filter(|c| *c != '\n')
+            .position(|c| c == tile)
+            .ok_or_else(|| ctx.error(input, format!("No `{tile}` tile on the map")))
+    };
+    let start_index = find_tile('S')?;
+
+    let x_size = input.lines().next().map_or(0, str::len);
 
     let start = ((start_index % x_size) - 1, (start_index / x_size) - 1);
 
@@ -33,9 +42,9 @@ pub fn input_generator(input: &str) -> Input {
             })
             .collect::<Vec<_>>(),
     )
-    .unwrap();
+    .ok_or_else(|| ctx.error(input, "No open tiles on the map"))?;
 
-    (grid, start, min_cheat)
+    Ok((grid, start, min_cheat))
 }
 
 #[inline]
@@ -76,12 +85,35 @@ pub fn solve_part2(input: &Input) -> Output {
     count_cheats(grid, start, *min_cheat, 20)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: usize = 20;
+    const TITLE: &'static str = "Race Condition";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -108,13 +140,13 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        let (grid, start, _) = &input_generator(sample());
+        let (grid, start, _) = &input_generator(sample()).unwrap();
         assert_eq!(44, solve_part1(&(grid.clone(), *start, 2)));
     }
 
     #[test]
     fn samples_part2() {
-        let (grid, start, _) = &input_generator(sample());
+        let (grid, start, _) = &input_generator(sample()).unwrap();
         assert_eq!(285, solve_part2(&(grid.clone(), *start, 50)));
     }
 }
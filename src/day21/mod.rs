@@ -3,6 +3,7 @@ use std::sync::LazyLock;
 
 use bimap::BiMap;
 
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 use cached::proc_macro::cached;
 use cached::SizedCache;
@@ -10,13 +11,26 @@ use cached::SizedCache;
 use itertools::Itertools;
 use pathfinding::grid::Grid;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{direction::Direction, point::Point};
+
 type Output = usize;
 type Pos = (usize, usize);
 type Input = Vec<String>;
 
 #[aoc_generator(day21)]
-pub fn input_generator(input: &str) -> Input {
-    input.lines().map(|l| l.to_string()).collect()
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(21, input);
+    input
+        .lines()
+        .map(|l| match l.strip_suffix('A') {
+            Some(num) if !num.is_empty() && num.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(l.to_string())
+            }
+            _ => Err(ctx.error(l, "Not a door code of digits followed by `A`")),
+        })
+        .collect()
 }
 
 static NUMERICS: LazyLock<BiMap<char, Pos>> = LazyLock::new(|| {
@@ -47,15 +61,6 @@ static DIRECTIONS: LazyLock<BiMap<char, Pos>> = LazyLock::new(|| {
     ])
 });
 
-static DIRS: LazyLock<BiMap<(isize, isize), char>> = LazyLock::new(|| {
-    BiMap::from_iter(vec![
-        ((1, 0), '>'),
-        ((0, 1), 'v'),
-        ((-1, 0), '<'),
-        ((0, -1), '^'),
-    ])
-});
-
 static NUMERICAL_KEYBOARD: LazyLock<Grid> = LazyLock::new(|| {
     Grid::from_coordinates(
         NUMERICS
@@ -104,15 +109,15 @@ fn traverse(
     let mut moves = vec![];
 
     if pos_a.0 < pos_b.0 {
-        moves.extend(['>'].repeat(pos_b.0 - pos_a.0));
+        moves.extend([Direction::Right].repeat(pos_b.0 - pos_a.0));
     } else {
-        moves.extend(['<'].repeat(pos_a.0 - pos_b.0));
+        moves.extend([Direction::Left].repeat(pos_a.0 - pos_b.0));
     }
 
     if pos_a.1 < pos_b.1 {
-        moves.extend(['v'].repeat(pos_b.1 - pos_a.1));
+        moves.extend([Direction::Down].repeat(pos_b.1 - pos_a.1));
     } else {
-        moves.extend(['^'].repeat(pos_a.1 - pos_b.1));
+        moves.extend([Direction::Up].repeat(pos_a.1 - pos_b.1));
     }
 
     moves
@@ -122,11 +127,8 @@ fn traverse(
             let mut cur_pos = pos_a;
 
             for dir in &moves {
-                let dir_move = DIRS.get_by_right(dir).unwrap();
-                let next_pos = (
-                    cur_pos.0 as isize + dir_move.0,
-                    cur_pos.1 as isize + dir_move.1,
-                );
+                let next_pos: (isize, isize) =
+                    (Point::from((cur_pos.0 as isize, cur_pos.1 as isize)) + (**dir).into()).into();
                 if next_pos.0 < 0
                     || next_pos.1 < 0
                     || !keyboard.has_vertex((next_pos.0 as usize, next_pos.1 as usize))
@@ -137,7 +139,7 @@ fn traverse(
             }
             Some(
                 once('A')
-                    .chain(moves.into_iter().copied())
+                    .chain(moves.into_iter().map(|dir| dir.arrow()))
                     .chain(once('A'))
                     .tuple_windows()
                     .map(|(b_a, b_b)| {
@@ -202,12 +204,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: usize = 21;
+    const TITLE: &'static str = "Keypad Conundrum";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -224,11 +249,14 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(126384, solve_part1(&input_generator(sample())));
+        assert_eq!(126384, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert!(true);
+        assert_eq!(
+            154115708116294,
+            solve_part2(&input_generator(sample()).unwrap())
+        );
     }
 }
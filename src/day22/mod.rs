@@ -1,18 +1,23 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::{FxHashMap, FxHashSet};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Num = i64;
 type Output = Num;
 type Input = Vec<Num>;
 
 #[aoc_generator(day22)]
-pub fn input_generator(input: &str) -> Input {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(22, input);
     input
         .lines()
-        .map(|line| line.parse::<Num>().unwrap())
-        .collect::<Vec<Num>>()
+        .map(|line| ctx.parse::<Num>(line))
+        .collect::<Result<Vec<Num>, ParseError>>()
 }
 
 fn generate(mut secret: Num) -> Num {
@@ -70,12 +75,35 @@ pub fn solve_part2(input: &Input) -> Output {
     *buy_options.values().max().unwrap()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: usize = 22;
+    const TITLE: &'static str = "Monkey Market";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -92,7 +120,7 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(37327623, solve_part1(&input_generator(sample1())));
+        assert_eq!(37327623, solve_part1(&input_generator(sample1()).unwrap()));
     }
 
     fn sample2() -> &'static str {
@@ -104,6 +132,6 @@ mod tests {
 
     #[test]
     fn samples_part2() {
-        assert_eq!(23, solve_part2(&input_generator(sample2())));
+        assert_eq!(23, solve_part2(&input_generator(sample2()).unwrap()));
     }
 }
@@ -1,23 +1,30 @@
 use std::cmp::max_by_key;
 
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 use fxhash::{FxHashMap, FxHashSet};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Output2 = String;
 type Input = Vec<(String, String)>;
 
 #[aoc_generator(day23)]
-pub fn input_generator(input: &str) -> Input {
-    input
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(23, input);
+    Ok(input
         .lines()
-        .map(|line| line.split_once("-").expect("No `-` delimiter"))
+        .map(|line| ctx.split_once(line, "-"))
+        .collect::<Result<Vec<(&str, &str)>, ParseError>>()?
+        .into_iter()
         .map(|(a, b)| (a.min(b), a.max(b)))
         .sorted_by(|(aa, ab), (ba, bb)| aa.cmp(ba).then(ab.cmp(bb)))
         .map(|(a, b)| (a.to_string(), b.to_string()))
-        .collect()
+        .collect())
 }
 
 /// Finds the maximum cliques in a graph
@@ -107,12 +114,35 @@ pub fn solve_part2(input: &Input) -> Output2 {
     .join(",")
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: usize = 23;
+    const TITLE: &'static str = "LAN Party";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output2;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -156,11 +186,14 @@ td-yn"
 
     #[test]
     fn samples_part1() {
-        assert_eq!(7, solve_part1(&input_generator(sample())));
+        assert_eq!(7, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!("co,de,ka,ta", solve_part2(&input_generator(sample())));
+        assert_eq!(
+            "co,de,ka,ta",
+            solve_part2(&input_generator(sample()).unwrap())
+        );
     }
 }
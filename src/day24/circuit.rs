@@ -0,0 +1,322 @@
+use std::fmt::Display;
+
+use fxhash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
+
+use super::{Gate, Num, Op};
+
+/// Index of a wire in a [`Circuit`].
+pub type WireId = usize;
+
+/// Why a gate network can not be compiled or evaluated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CircuitError {
+    /// An input wire, which no gate drives, was not given a value.
+    MissingWire(String),
+    /// Wires feeding back into each other, each one an input of the next gate in the list.
+    Cycle(Vec<String>),
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::MissingWire(wire) => write!(f, "No value for input wire {wire}"),
+            CircuitError::Cycle(wires) => write!(f, "Wires form a cycle: {}", wires.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+#[derive(Clone, Debug)]
+struct CompiledGate {
+    inputs: Vec<WireId>,
+    out: WireId,
+    op: Op,
+}
+
+/// A gate network with interned wire names and its gates in topological order, so that it can be
+/// evaluated in a single pass over the gates.
+///
+/// Values are kept in a slice indexed by [`WireId`]. Gates combine whole words bitwise, so a
+/// single pass can also evaluate up to [`Num::BITS`] independent sets of inputs at once.
+#[derive(Clone, Debug)]
+pub struct Circuit {
+    names: Vec<String>,
+    ids: FxHashMap<String, WireId>,
+    /// Wires not driven by any gate.
+    inputs: Vec<WireId>,
+    gates: Vec<CompiledGate>,
+    /// Index into `gates` of the gate driving a wire.
+    driver: Vec<Option<usize>>,
+}
+
+impl Circuit {
+    pub fn new(gates: &FxHashMap<String, Gate>) -> Result<Self, CircuitError> {
+        let mut names = vec![];
+        let mut ids = FxHashMap::default();
+        let mut intern = |name: &str| {
+            *ids.entry(name.to_owned()).or_insert_with(|| {
+                names.push(name.to_owned());
+                names.len() - 1
+            })
+        };
+        // Sorted for stable ids
+        let unsorted = gates
+            .values()
+            .sorted_by(|lhs, rhs| lhs.out.cmp(&rhs.out))
+            .map(|gate| CompiledGate {
+                inputs: gate.inputs.iter().map(|input| intern(input)).collect(),
+                out: intern(&gate.out),
+                op: gate.op,
+            })
+            .collect::<Vec<_>>();
+        Self::sort(names, ids, unsorted)
+    }
+
+    /// Orders the gates topologically.
+    fn sort(
+        names: Vec<String>,
+        ids: FxHashMap<String, WireId>,
+        unsorted: Vec<CompiledGate>,
+    ) -> Result<Self, CircuitError> {
+        let mut driver = vec![None; names.len()];
+        for (idx, gate) in unsorted.iter().enumerate() {
+            driver[gate.out] = Some(idx);
+        }
+        let inputs = (0..names.len())
+            .filter(|wire| driver[*wire].is_none())
+            .collect();
+
+        // Kahn's algorithm, with the number of inputs still waiting for a value per gate
+        let mut readers = vec![vec![]; names.len()];
+        let mut pending = vec![0; unsorted.len()];
+        for (idx, gate) in unsorted.iter().enumerate() {
+            for &wire in &gate.inputs {
+                if driver[wire].is_some() {
+                    readers[wire].push(idx);
+                    pending[idx] += 1;
+                }
+            }
+        }
+        let mut ready = (0..unsorted.len())
+            .filter(|idx| pending[*idx] == 0)
+            .collect::<Vec<_>>();
+        let mut sorted = Vec::with_capacity(unsorted.len());
+        while let Some(idx) = ready.pop() {
+            sorted.push(unsorted[idx].clone());
+            for reader in &readers[unsorted[idx].out] {
+                pending[*reader] -= 1;
+                if pending[*reader] == 0 {
+                    ready.push(*reader);
+                }
+            }
+        }
+
+        if sorted.len() < unsorted.len() {
+            // Every gate left over has an input driven by another left over gate, so following
+            // those inputs has to run into a cycle eventually.
+            let mut path = vec![];
+            let mut wire = unsorted[pending.iter().position(|n| *n > 0).unwrap()].out;
+            while !path.contains(&wire) {
+                path.push(wire);
+                let gate = &unsorted[driver[wire].unwrap()];
+                wire = gate
+                    .inputs
+                    .iter()
+                    .copied()
+                    .find(|input| driver[*input].is_some_and(|idx| pending[idx] > 0))
+                    .unwrap();
+            }
+            let start = path.iter().position(|w| *w == wire).unwrap();
+            let mut cycle = path[start..]
+                .iter()
+                .rev()
+                .map(|wire| names[*wire].clone())
+                .collect::<Vec<_>>();
+            // Start at the smallest name, so the same cycle is always reported the same way
+            let first = cycle.iter().position_min().unwrap();
+            cycle.rotate_left(first);
+            return Err(CircuitError::Cycle(cycle));
+        }
+
+        for (idx, gate) in sorted.iter().enumerate() {
+            driver[gate.out] = Some(idx);
+        }
+        Ok(Self {
+            names,
+            ids,
+            inputs,
+            gates: sorted,
+            driver,
+        })
+    }
+
+    /// The same circuit, but with the gates driving `lhs` and `rhs` exchanging their outputs.
+    pub fn with_swapped(&self, lhs: WireId, rhs: WireId) -> Result<Self, CircuitError> {
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| CompiledGate {
+                out: match gate.out {
+                    out if out == lhs => rhs,
+                    out if out == rhs => lhs,
+                    out => out,
+                },
+                ..gate.clone()
+            })
+            .collect();
+        Self::sort(self.names.clone(), self.ids.clone(), gates)
+    }
+
+    pub fn id(&self, name: &str) -> Option<WireId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, wire: WireId) -> &str {
+        &self.names[wire]
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Wires driven by a gate, in topological order.
+    pub fn outputs(&self) -> impl Iterator<Item = WireId> + '_ {
+        self.gates.iter().map(|gate| gate.out)
+    }
+
+    /// Gate driven wires that `wire` depends on, including `wire` itself unless it is an input.
+    pub fn fan_in(&self, wire: WireId) -> FxHashSet<WireId> {
+        let mut cone = FxHashSet::default();
+        let mut todo = vec![wire];
+        while let Some(wire) = todo.pop() {
+            if let Some(idx) = self.driver[wire] {
+                if cone.insert(wire) {
+                    todo.extend(&self.gates[idx].inputs);
+                }
+            }
+        }
+        cone
+    }
+
+    /// Inputs of the gate driving `wire`, `None` if `wire` is an input.
+    pub fn gate_inputs(&self, wire: WireId) -> Option<&[WireId]> {
+        self.driver[wire].map(|idx| self.gates[idx].inputs.as_slice())
+    }
+
+    /// Wires not driven by any gate, which need a value before evaluating the circuit.
+    pub fn inputs(&self) -> &[WireId] {
+        &self.inputs
+    }
+
+    /// Wires named `<prefix><index>`, ordered by index, i.e. least significant bit first.
+    pub fn bus(&self, prefix: char) -> Vec<WireId> {
+        (0..self.names.len())
+            .filter_map(|wire| {
+                let index = self.names[wire]
+                    .strip_prefix(prefix)?
+                    .parse::<usize>()
+                    .ok()?;
+                Some((index, wire))
+            })
+            .sorted()
+            .map(|(_, wire)| wire)
+            .collect()
+    }
+
+    /// Evaluates all gates, reading the values of the input wires from `values` and writing
+    /// the others.
+    pub fn run(&self, values: &mut [Num]) {
+        for gate in &self.gates {
+            let value = gate.op.apply(gate.inputs.iter().map(|wire| values[*wire]));
+            values[gate.out] = value;
+        }
+    }
+
+    /// Like [`Circuit::run`], but keeps the values of the wires in `cut` as given, even if a gate
+    /// drives them.
+    pub fn run_cut(&self, values: &mut [Num], cut: &[WireId]) {
+        for gate in self.gates.iter().filter(|gate| !cut.contains(&gate.out)) {
+            let value = gate.op.apply(gate.inputs.iter().map(|wire| values[*wire]));
+            values[gate.out] = value;
+        }
+    }
+
+    /// Values of all wires, for the input wires given by name.
+    pub fn evaluate(&self, inputs: &FxHashMap<String, Num>) -> Result<Vec<Num>, CircuitError> {
+        let mut values = vec![0; self.names.len()];
+        for wire in &self.inputs {
+            values[*wire] = *inputs
+                .get(&self.names[*wire])
+                .ok_or_else(|| CircuitError::MissingWire(self.names[*wire].clone()))?;
+        }
+        self.run(&mut values);
+        Ok(values)
+    }
+
+    /// Number made of the bits on the wires of a [`Circuit::bus`].
+    pub fn read(&self, values: &[Num], prefix: char) -> Num {
+        self.bus(prefix)
+            .iter()
+            .rev()
+            .fold(0, |out, wire| (out << 1) | (values[*wire] & 1))
+    }
+
+    /// Puts the bits of `number` on the wires of a [`Circuit::bus`].
+    pub fn write(&self, values: &mut [Num], prefix: char, number: Num) {
+        for (bit, wire) in self.bus(prefix).into_iter().enumerate() {
+            values[wire] = number.checked_shr(bit as u32).unwrap_or(0) & 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gates(lines: &str) -> FxHashMap<String, Gate> {
+        lines
+            .lines()
+            .map(|line| line.parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_in_order() {
+        let circuit = Circuit::new(&gates(
+            "abc AND x01 -> z01\nx00 XOR y00 -> abc\nx00 OR y00 -> z00",
+        ))
+        .unwrap();
+        assert_eq!(3, circuit.inputs().len());
+        let mut values = vec![0; circuit.wire_count()];
+        circuit.write(&mut values, 'x', 0b11);
+        circuit.run(&mut values);
+        assert_eq!(0b11, circuit.read(&values, 'z'));
+        assert_eq!(
+            vec!["z00", "z01"],
+            circuit
+                .bus('z')
+                .iter()
+                .map(|w| circuit.name(*w))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let inputs = [("x00".to_owned(), 1)].into_iter().collect();
+        let circuit = Circuit::new(&gates("x00 AND y00 -> z00")).unwrap();
+        assert_eq!(
+            Err(CircuitError::MissingWire("y00".to_owned())),
+            circuit.evaluate(&inputs)
+        );
+
+        let error = Circuit::new(&gates(
+            "x00 AND aaa -> bbb\nbbb OR y00 -> ccc\nccc XOR x00 -> aaa\nccc AND y00 -> z00",
+        ))
+        .unwrap_err();
+        assert_eq!("Wires form a cycle: aaa -> bbb -> ccc", error.to_string());
+    }
+}
@@ -0,0 +1,278 @@
+//! Export of a gate network in the [dot](https://graphviz.org/doc/info/lang.html) language.
+//!
+//! Using dot it can be rendered to a file (e.g., by calling
+//! `dot -Tsvg gate_network.dot > gate_network.svg`) where:
+//! * Inputs (`xnn`, `ynn`) are rendered as triangles
+//! * $\oplus{}$ is rendered as a trapezoid
+//! * $\lor{}$ is rendered as a diamond
+//! * $\wedge{}$ is rendered as a circle
+//! * NAND, NOR and XNOR are rendered as a double circle, a diamond with marked corners and an
+//!   inverted trapezoid
+//! * NOT is rendered as an inverted triangle and MUX as a box
+//! * Output (`znn`) nodes with red borders are nodes outputing the wrong bit for the computation
+//!   of $x + y$, see [`Dot::with_broken_bits`].
+//! * Filled nodes are nodes which need to be swapped for the correct result, see
+//!   [`Dot::with_swaps`].
+use std::{fmt::Display, path::Path};
+
+use anyhow::{Context, Result};
+use fxhash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
+
+use super::{circuit::Circuit, Gate, Num};
+
+/// Options for exporting a gate network, written out by its [`Display`] implementation.
+#[derive(Clone, Debug)]
+pub struct Dot<'a> {
+    gates: &'a FxHashMap<String, Gate>,
+    broken: FxHashSet<String>,
+    swapped: FxHashSet<String>,
+    clusters: bool,
+    ranks: bool,
+}
+
+impl<'a> Dot<'a> {
+    pub fn new(gates: &'a FxHashMap<String, Gate>) -> Self {
+        Self {
+            gates,
+            broken: FxHashSet::default(),
+            swapped: FxHashSet::default(),
+            clusters: false,
+            ranks: false,
+        }
+    }
+
+    /// Outlines the `z` wires in red which do not match the sum of the `x` and `y` wires of
+    /// `inputs`.
+    pub fn with_broken_bits(mut self, inputs: &FxHashMap<String, Num>) -> Result<Self> {
+        let circuit = Circuit::new(self.gates)?;
+        let values = circuit.evaluate(inputs)?;
+        let (sum, carry) = circuit
+            .read(&values, 'x')
+            .overflowing_add(circuit.read(&values, 'y'));
+        // Buses can be wider than `Num`, the carry out of the sum goes to the bit above it
+        let expected = |bit: usize| match (bit as u32).checked_sub(Num::BITS) {
+            None => (sum >> bit) & 1,
+            Some(0) => Num::from(carry),
+            Some(_) => 0,
+        };
+        self.broken = circuit
+            .bus('z')
+            .into_iter()
+            .enumerate()
+            .filter(|(bit, wire)| (values[*wire] & 1) != expected(*bit))
+            .map(|(_, wire)| circuit.name(wire).to_owned())
+            .collect();
+        Ok(self)
+    }
+
+    /// Fills the nodes of the wires whose gates have to exchange their outputs.
+    pub fn with_swaps<S: AsRef<str>>(mut self, wires: impl IntoIterator<Item = S>) -> Self {
+        self.swapped = wires
+            .into_iter()
+            .map(|wire| wire.as_ref().to_owned())
+            .collect();
+        self
+    }
+
+    /// Groups the wires by the highest input bit they depend on, which is the bit of the adder
+    /// they belong to.
+    pub fn with_clusters(mut self) -> Self {
+        self.clusters = true;
+        self
+    }
+
+    /// Lays out the network from left to right, with all inputs in the first and all outputs in
+    /// the last rank.
+    pub fn with_ranks(mut self) -> Self {
+        self.ranks = true;
+        self
+    }
+
+    /// Writes the network to `path`.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Highest index of an `x` or `y` wire each wire depends on.
+    fn bits(&self) -> FxHashMap<&str, usize> {
+        let mut bits = self
+            .wires()
+            .into_iter()
+            .filter_map(|wire| {
+                let index = wire.strip_prefix(['x', 'y'])?.parse().ok()?;
+                Some((wire, index))
+            })
+            .collect::<FxHashMap<_, _>>();
+        // Iterating until nothing changes instead of sorting, as the network may have cycles
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for gate in self.gates.values() {
+                let Some(bit) = gate
+                    .inputs
+                    .iter()
+                    .filter_map(|wire| bits.get(wire.as_str()).copied())
+                    .max()
+                else {
+                    continue;
+                };
+                if bits.get(gate.out.as_str()).is_none_or(|old| *old < bit) {
+                    bits.insert(&gate.out, bit);
+                    changed = true;
+                }
+            }
+        }
+        bits
+    }
+
+    /// All wire names, sorted.
+    fn wires(&self) -> Vec<&'a str> {
+        self.gates
+            .values()
+            .flat_map(|gate| gate.inputs.iter().chain([&gate.out]))
+            .map(String::as_str)
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    fn node(&self, wire: &str) -> String {
+        let shape = self
+            .gates
+            .get(wire)
+            .map_or("triangle", |gate| gate.select_style());
+        let mut attributes = vec![format!("shape={shape}")];
+        if self.broken.contains(wire) {
+            attributes.push("color=red".to_owned());
+        }
+        if self.swapped.contains(wire) {
+            attributes.push("style=filled".to_owned());
+        }
+        format!("{wire}[{}];", attributes.join(", "))
+    }
+}
+
+impl Display for Dot<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "digraph {{")?;
+        if self.ranks {
+            writeln!(f, "\trankdir=LR;")?;
+            if self.clusters {
+                // Allows rank constraints across clusters
+                writeln!(f, "\tnewrank=true;")?;
+            }
+        }
+
+        let wires = self.wires();
+        if self.clusters {
+            let bits = self.bits();
+            let clusters = wires
+                .iter()
+                .into_group_map_by(|wire| bits.get(*wire).copied());
+            for (bit, wires) in clusters.iter().sorted() {
+                let Some(bit) = bit else {
+                    continue;
+                };
+                writeln!(f, "\tsubgraph cluster_{bit:02} {{")?;
+                writeln!(f, "\t\tlabel=\"bit {bit}\";")?;
+                for wire in wires {
+                    writeln!(f, "\t\t{}", self.node(wire))?;
+                }
+                writeln!(f, "\t}}")?;
+            }
+            for wire in clusters.get(&None).into_iter().flatten() {
+                writeln!(f, "\t{}", self.node(wire))?;
+            }
+        } else {
+            for wire in &wires {
+                writeln!(f, "\t{}", self.node(wire))?;
+            }
+        }
+
+        for gate in self.gates.values().sorted_by_key(|gate| &gate.out) {
+            for input in &gate.inputs {
+                writeln!(f, "\t{input} -> {};", gate.out)?;
+            }
+        }
+
+        if self.ranks {
+            let inputs = wires
+                .iter()
+                .filter(|wire| !self.gates.contains_key(**wire))
+                .join("; ");
+            let outputs = wires.iter().filter(|wire| wire.starts_with('z')).join("; ");
+            writeln!(f, "\t{{ rank=source; {inputs}; }}")?;
+            writeln!(f, "\t{{ rank=sink; {outputs}; }}")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gates() -> FxHashMap<String, Gate> {
+        "x00 XOR y00 -> z00\nx00 OR y00 -> z01"
+            .lines()
+            .map(|line| line.parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect()
+    }
+
+    #[test]
+    fn exports_network() {
+        let gates = gates();
+        assert_eq!(
+            "digraph {
+\tx00[shape=triangle];
+\ty00[shape=triangle];
+\tz00[shape=trapezium];
+\tz01[shape=diamond];
+\tx00 -> z00;
+\ty00 -> z00;
+\tx00 -> z01;
+\ty00 -> z01;
+}",
+            Dot::new(&gates).to_string()
+        );
+    }
+
+    #[test]
+    fn exports_options() {
+        let gates = gates();
+        let inputs = [("x00".to_owned(), 1), ("y00".to_owned(), 0)]
+            .into_iter()
+            .collect();
+        let dot = Dot::new(&gates)
+            .with_broken_bits(&inputs)
+            .unwrap()
+            .with_swaps(["z00"])
+            .with_clusters()
+            .with_ranks()
+            .to_string();
+        assert!(
+            dot.contains("\tsubgraph cluster_00 {\n\t\tlabel=\"bit 0\";\n\t\tx00[shape=triangle];")
+        );
+        assert!(dot.contains("\t\tz00[shape=trapezium, style=filled];"));
+        assert!(dot.contains("\t\tz01[shape=diamond, color=red];"));
+        assert!(dot.contains("\t{ rank=source; x00; y00; }\n\t{ rank=sink; z00; z01; }\n}"));
+    }
+
+    #[test]
+    fn marks_bits_beyond_num() {
+        let gates = (0..=Num::BITS)
+            .map(|bit| format!("x00 AND y00 -> z{bit:02}").parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect();
+        let inputs = [("x00".to_owned(), 1), ("y00".to_owned(), 1)]
+            .into_iter()
+            .collect();
+        let dot = Dot::new(&gates).with_broken_bits(&inputs).unwrap();
+        assert_eq!(Num::BITS as usize, dot.broken.len());
+        assert!(!dot.broken.contains("z01"));
+    }
+}
@@ -0,0 +1,406 @@
+//! Proof that a gate network adds its `x` and `y` inputs, by comparing it with a
+//! [`ripple_carry_adder`] of the same width.
+//!
+//! Both networks evaluate [`LANES`] additions in a single pass: the value of every wire is a
+//! word, with bit `lane` of the word belonging to addition `lane`.
+//!
+//! Small additions are checked exhaustively, ordered by their larger operand, so the first one
+//! going wrong is the smallest counterexample. Counterexamples with larger operands are only
+//! searched for around the bit a proof fails at, so they are the smallest one found there, but
+//! not necessarily the smallest one overall. The rest is proven bit by bit, by cutting the
+//! network at its carries: the wire carrying the same values as a carry of the reference for
+//! random additions is assumed to be that carry, and the sum and carry out of every bit are
+//! checked for all 8 combinations of the two input bits and the carry in. If that holds for every
+//! bit, the carries are correct by induction and with them all sums.
+use std::fmt::Display;
+
+use anyhow::{ensure, Result};
+use fxhash::FxHashMap;
+use itertools::Itertools;
+
+use super::{
+    circuit::{Circuit, WireId},
+    repair::{ripple_carry_adder, Adder},
+    Gate, Num,
+};
+
+/// Additions evaluated in one pass over the gates.
+const LANES: usize = Num::BITS as usize;
+/// Smallest additions checked one by one, before proving the others.
+const EXHAUSTIVE: usize = 1 << 16;
+/// Passes of random additions to find the carries of the network with.
+const SIGNATURE_PASSES: usize = 4;
+
+/// An addition the network gets wrong.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Counterexample {
+    pub x: Num,
+    pub y: Num,
+    /// Sum computed by the network.
+    pub z: Num,
+}
+
+impl Counterexample {
+    /// Mask of the `z` bits differing from `x + y`.
+    pub fn wrong_bits(&self) -> Num {
+        self.z ^ (self.x + self.y)
+    }
+}
+
+impl Display for Counterexample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wrong = self.wrong_bits();
+        write!(
+            f,
+            "{} + {} = {}, but the network computes {} (wrong bits: {})",
+            self.x,
+            self.y,
+            self.x + self.y,
+            self.z,
+            (0..Num::BITS)
+                .filter(|bit| (wrong >> bit) & 1 == 1)
+                .map(|bit| format!("z{bit:02}"))
+                .join(", ")
+        )
+    }
+}
+
+/// Result of [`check_adder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The network adds all inputs correctly.
+    Equivalent,
+    /// An addition the network gets wrong. It is the smallest one if both operands are below
+    /// `2^8`, otherwise only the smallest one around the bit the proof failed at.
+    Counterexample(Counterexample),
+    /// No counterexample was found, but the network could not be cut at the carry into bit
+    /// `bit`, so it is not proven either.
+    Unknown { bit: usize },
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Equivalent => write!(f, "The network is an adder"),
+            Verdict::Counterexample(counterexample) => write!(f, "{counterexample}"),
+            Verdict::Unknown { bit } => write!(
+                f,
+                "No counterexample found, but the proof got stuck at bit {bit}"
+            ),
+        }
+    }
+}
+
+/// Additions of numbers below `2^width`, ordered by the larger operand, then by `x` and `y`.
+fn additions(width: usize) -> impl Iterator<Item = (Num, Num)> {
+    (0..1 << width).flat_map(|max| {
+        (0..max)
+            .map(move |x| (x, max))
+            .chain((0..=max).map(move |y| (max, y)))
+    })
+}
+
+/// Deterministic pseudo random words, see <https://prng.di.unimi.it/splitmix64.c>.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A network and the reference adder of the same width, evaluated side by side.
+struct Miter {
+    network: Circuit,
+    network_bus: Adder,
+    reference: Circuit,
+    reference_bus: Adder,
+}
+
+impl Miter {
+    fn width(&self) -> usize {
+        self.network_bus.x.len()
+    }
+
+    /// Values of all wires of `circuit`, with `x[bit]` and `y[bit]` the words on the input
+    /// wires of `bit`.
+    fn run(circuit: &Circuit, bus: &Adder, x: &[Num], y: &[Num]) -> Vec<Num> {
+        let mut values = vec![0; circuit.wire_count()];
+        for (bit, (x_wire, y_wire)) in bus.x.iter().zip(&bus.y).enumerate() {
+            values[*x_wire] = x[bit];
+            values[*y_wire] = y[bit];
+        }
+        circuit.run(&mut values);
+        values
+    }
+
+    /// First of at most [`LANES`] additions the network gets wrong.
+    fn first_mismatch(&self, additions: &[(Num, Num)]) -> Option<Counterexample> {
+        let word = |bit: usize, pick: fn(&(Num, Num)) -> Num| {
+            additions
+                .iter()
+                .enumerate()
+                .fold(0, |word, (lane, addition)| {
+                    word | ((pick(addition) >> bit) & 1) << lane
+                })
+        };
+        let (x, y): (Vec<_>, Vec<_>) = (0..self.width())
+            .map(|bit| (word(bit, |(x, _)| *x), word(bit, |(_, y)| *y)))
+            .unzip();
+        let network = Self::run(&self.network, &self.network_bus, &x, &y);
+        let reference = Self::run(&self.reference, &self.reference_bus, &x, &y);
+        let lanes = Num::MAX >> (LANES - additions.len());
+        let diff = self
+            .network_bus
+            .z
+            .iter()
+            .zip(&self.reference_bus.z)
+            .fold(0, |diff, (lhs, rhs)| {
+                diff | (network[*lhs] ^ reference[*rhs])
+            })
+            & lanes;
+        if diff == 0 {
+            return None;
+        }
+        let lane = diff.trailing_zeros();
+        let (x, y) = additions[lane as usize];
+        let z = self
+            .network_bus
+            .z
+            .iter()
+            .rev()
+            .fold(0, |z, wire| (z << 1) | ((network[*wire] >> lane) & 1));
+        Some(Counterexample { x, y, z })
+    }
+
+    /// Wires of the network carrying the same values as the carry out of each bit of the
+    /// reference for random additions, `None` where there is no such wire. The carry out of the
+    /// last bit is always the last output of the network.
+    fn carries(&self) -> Vec<Option<WireId>> {
+        let mut state = 24;
+        let mut network_signatures = vec![vec![]; self.network.wire_count()];
+        let mut reference_signatures = vec![vec![]; self.reference.wire_count()];
+        for _ in 0..SIGNATURE_PASSES {
+            let mut random = || {
+                (0..self.width())
+                    .map(|_| splitmix64(&mut state) as Num)
+                    .collect_vec()
+            };
+            let (x, y) = (random(), random());
+            let network = Self::run(&self.network, &self.network_bus, &x, &y);
+            let reference = Self::run(&self.reference, &self.reference_bus, &x, &y);
+            for (signature, value) in network_signatures.iter_mut().zip(network) {
+                signature.push(value);
+            }
+            for (signature, value) in reference_signatures.iter_mut().zip(reference) {
+                signature.push(value);
+            }
+        }
+        let mut wires = FxHashMap::default();
+        for (wire, signature) in network_signatures.into_iter().enumerate() {
+            wires.entry(signature).or_insert(wire);
+        }
+        // Named as in `ripple_carry_adder`. The carry out of the last bit has to be the last
+        // output, not just any wire carrying the same values.
+        (0..self.width())
+            .map(|bit| {
+                if bit + 1 == self.width() {
+                    return Some(self.network_bus.z[bit + 1]);
+                }
+                let carry = self.reference.id(&format!("c{bit:02}"))?;
+                wires.get(&reference_signatures[carry]).copied()
+            })
+            .collect()
+    }
+
+    /// Proves the network adds correctly, `Err` has the lowest bit that could not be proven.
+    fn prove(&self) -> Result<(), usize> {
+        let carries = self.carries();
+        // Lane `n` has bit 0 of `n` on x, bit 1 on y and bit 2 on the carry in
+        let (x, y, carry_in) = (0b1010_1010, 0b1100_1100, 0b1111_0000);
+        let mut values = vec![0; self.network.wire_count()];
+        for bit in 0..self.width() {
+            let carry_out = carries[bit].ok_or(bit)?;
+            let mut cut = vec![self.network_bus.x[bit], self.network_bus.y[bit]];
+            values[cut[0]] = x;
+            values[cut[1]] = y;
+            let carry_in = if bit == 0 {
+                0
+            } else {
+                let wire = carries[bit - 1].ok_or(bit)?;
+                cut.push(wire);
+                values[wire] = carry_in;
+                carry_in
+            };
+            let sum = self.network_bus.z[bit];
+            if !self.is_cut_by(sum, &cut) || !self.is_cut_by(carry_out, &cut) {
+                return Err(bit);
+            }
+            self.network.run_cut(&mut values, &cut);
+            if (values[sum] ^ (x ^ y ^ carry_in)) & 0xff != 0
+                || (values[carry_out] ^ ((x & y) | (carry_in & (x ^ y)))) & 0xff != 0
+            {
+                return Err(bit);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether all paths from the inputs of the network to `wire` pass through `cut`.
+    fn is_cut_by(&self, wire: WireId, cut: &[WireId]) -> bool {
+        let mut seen = vec![false; self.network.wire_count()];
+        let mut todo = vec![wire];
+        while let Some(wire) = todo.pop() {
+            if cut.contains(&wire) || std::mem::replace(&mut seen[wire], true) {
+                continue;
+            }
+            match self.network.gate_inputs(wire) {
+                Some(inputs) => todo.extend(inputs),
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+/// Checks whether `gates` computes `z = x + y` for all inputs.
+///
+/// Additions with both operands below `2^8` are checked exhaustively, so a counterexample
+/// among them is the smallest one by its larger operand, then by `x` and `y`. Beyond that the
+/// network is proven correct bit by bit. If that fails at a bit, all combinations of the bits
+/// around it are tried, reporting the smallest of them going wrong. That one is not guaranteed
+/// to be the smallest counterexample overall.
+pub fn check_adder(gates: &FxHashMap<String, Gate>) -> Result<Verdict> {
+    let network = Circuit::new(gates)?;
+    let network_bus = Adder::new(&network)?;
+    let width = network_bus.x.len();
+    ensure!(
+        (1..Num::BITS as usize).contains(&width),
+        "Only adders of 1 to {} bits are supported, found {width} bits",
+        Num::BITS - 1
+    );
+    let reference = Circuit::new(&ripple_carry_adder(width))?;
+    let reference_bus = Adder::new(&reference)?;
+    let miter = Miter {
+        network,
+        network_bus,
+        reference,
+        reference_bus,
+    };
+
+    for chunk in &additions(width).take(EXHAUSTIVE).chunks(LANES) {
+        if let Some(counterexample) = miter.first_mismatch(&chunk.collect_vec()) {
+            return Ok(Verdict::Counterexample(counterexample));
+        }
+    }
+    if 2 * width <= EXHAUSTIVE.ilog2() as usize {
+        return Ok(Verdict::Equivalent);
+    }
+
+    let Err(bit) = miter.prove() else {
+        return Ok(Verdict::Equivalent);
+    };
+    // The two input bits of the bit the proof failed at, the one below for its carry in and
+    // the one above for its carry out
+    let low = bit.saturating_sub(1);
+    let mask: Num = (1 << width) - 1;
+    let candidates = (0..LANES)
+        .map(|bits| {
+            let x = (bits & 0b111) << low;
+            let y = (bits >> 3) << low;
+            (x & mask, y & mask)
+        })
+        .sorted_by_key(|(x, y)| (*x.max(y), *x, *y))
+        .dedup()
+        .collect_vec();
+    Ok(match miter.first_mismatch(&candidates) {
+        Some(counterexample) => Verdict::Counterexample(counterexample),
+        None => Verdict::Unknown { bit },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day24::{repair::swap_outputs, Op};
+
+    #[test]
+    fn proves_adders() {
+        for width in (1..=12).chain([32, 45, 63]) {
+            assert_eq!(
+                Verdict::Equivalent,
+                check_adder(&ripple_carry_adder(width)).unwrap(),
+                "width {width}"
+            );
+        }
+        // Carries selected by the sum bit instead of or-ing both terms
+        let mut gates = ripple_carry_adder(32);
+        for bit in 1..31 {
+            let carry = format!("c{bit:02}");
+            let gate = Gate::new(
+                Op::Mux,
+                &[
+                    &format!("s{bit:02}"),
+                    &format!("a{bit:02}"),
+                    &format!("c{:02}", bit - 1),
+                ],
+                &carry,
+            );
+            gates.insert(carry, gate.unwrap());
+        }
+        assert_eq!(Verdict::Equivalent, check_adder(&gates).unwrap());
+
+        // The real carry out on another wire, with the top output only getting one of its terms
+        let mut gates = ripple_carry_adder(45);
+        let mut carry = gates.remove("z45").unwrap();
+        carry.out = "c44".to_owned();
+        gates.insert("c44".to_owned(), carry);
+        gates.insert(
+            "z45".to_owned(),
+            Gate::new(Op::And, &["a44", "b44"], "z45").unwrap(),
+        );
+        let Verdict::Counterexample(counterexample) = check_adder(&gates).unwrap() else {
+            panic!("Wrong carry out not detected");
+        };
+        assert_eq!((1 << 44, 1 << 44), (counterexample.x, counterexample.y));
+        assert_eq!(1 << 45, counterexample.wrong_bits());
+    }
+
+    #[test]
+    fn finds_smallest_counterexample() {
+        let mut gates = ripple_carry_adder(12);
+        swap_outputs(&mut gates, "z03", "b03").unwrap();
+        let Verdict::Counterexample(counterexample) = check_adder(&gates).unwrap() else {
+            panic!("Swapped outputs not detected");
+        };
+        assert_eq!(
+            "4 + 4 = 8, but the network computes 16 (wrong bits: z03, z04)",
+            counterexample.to_string()
+        );
+        let circuit = Circuit::new(&gates).unwrap();
+        let mut values = vec![0; circuit.wire_count()];
+        for (x, y) in additions(12).take_while(|addition| *addition != (4, 4)) {
+            circuit.write(&mut values, 'x', x);
+            circuit.write(&mut values, 'y', y);
+            circuit.run(&mut values);
+            assert_eq!(x + y, circuit.read(&values, 'z'), "{x} + {y}");
+        }
+    }
+
+    #[test]
+    fn finds_wide_counterexample() {
+        let mut gates = ripple_carry_adder(45);
+        swap_outputs(&mut gates, "z30", "b30").unwrap();
+        let Verdict::Counterexample(counterexample) = check_adder(&gates).unwrap() else {
+            panic!("Swapped outputs not detected");
+        };
+        assert_eq!(0b11 << 30, counterexample.wrong_bits());
+        assert_eq!((1 << 29, 1 << 29), (counterexample.x, counterexample.y));
+
+        gates.remove("z45");
+        assert_eq!(
+            "Not an adder: 45 x bits and 45 y bits, but 45 z bits",
+            check_adder(&gates).unwrap_err().to_string()
+        );
+    }
+}
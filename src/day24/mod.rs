@@ -1,221 +1,256 @@
-use std::io::Write;
-use std::{fs::File, str::FromStr};
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
 
+use anyhow::{bail, Context, Result};
 use aoc_runner_derive::{aoc, aoc_generator};
-use fxhash::{FxHashMap, FxHashSet};
+use fxhash::FxHashMap;
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
+mod circuit;
+pub mod dot;
+pub mod equivalence;
+pub mod netlist;
+pub mod repair;
+
+pub use circuit::{Circuit, CircuitError, WireId};
+pub use dot::Dot;
+
 type Num = usize;
 type Output = Num;
 type Output2 = String;
 type Input = (FxHashMap<String, Num>, FxHashMap<String, Gate>);
 
-#[derive(Debug)]
-pub struct Gate {
-    pub out: String,
-    pub in1: String,
-    pub in2: String,
-    pub op_name: String,
-    op: fn(Num, Num) -> Num,
+/// Logic function of a [`Gate`]. All operations work bitwise on whole words.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Op {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+    Not,
+    /// Inputs `select`, `low` and `high`, outputs `high` where `select` is set and `low`
+    /// elsewhere.
+    Mux,
 }
 
-impl Gate {
-    fn execute(&self, inputs: &mut FxHashMap<String, Num>, gates: &FxHashMap<String, Gate>) -> Num {
-        if let Some(output) = inputs.get(&self.out) {
-            *output
-        } else {
-            let inp1 = if let Some(inp1) = inputs.get(&self.in1) {
-                *inp1
-            } else {
-                gates
-                    .get(&self.in1)
-                    .unwrap_or_else(|| panic!("Gate {} not found", self.in1))
-                    .execute(inputs, gates)
-            };
-            let inp2 = if let Some(inp2) = inputs.get(&self.in2) {
-                *inp2
-            } else {
-                gates
-                    .get(&self.in2)
-                    .unwrap_or_else(|| panic!("Gate {} not found", self.in2))
-                    .execute(inputs, gates)
-            };
-            let output = (self.op)(inp1, inp2);
-            inputs
-                .entry(self.out.clone())
-                .and_modify(|_| {
-                    unreachable!("Tried to enter a different value for an existin gate")
-                })
-                .or_insert(output);
-            output
+impl Op {
+    pub const ALL: [Self; 8] = [
+        Self::And,
+        Self::Or,
+        Self::Xor,
+        Self::Nand,
+        Self::Nor,
+        Self::Xnor,
+        Self::Not,
+        Self::Mux,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::Xor => "XOR",
+            Op::Nand => "NAND",
+            Op::Nor => "NOR",
+            Op::Xnor => "XNOR",
+            Op::Not => "NOT",
+            Op::Mux => "MUX",
         }
     }
 
-    /// Outputs the gates connection in `dot` format
-    pub fn print_inputs(&self, gates: &FxHashMap<String, Gate>) -> String {
-        let mut output = if let Some(inp1) = gates.get(&self.in1) {
-            inp1.print_inputs(gates)
-        } else {
-            format!("{}[shape=triangle];\n", self.in1)
-        };
-        output.push_str(&if let Some(inp2) = gates.get(&self.in2) {
-            inp2.print_inputs(gates)
-        } else {
-            format!("{}[shape=triangle];\n", self.in2)
-        });
-        output.push_str(&format!(
-            "{}[shape={}];\n{} -> {};\n{} -> {};\n",
-            self.out,
-            self.select_style(),
-            self.in1,
-            self.out,
-            self.in2,
-            self.out
-        ));
-        output
+    /// Numbers of inputs the operation takes.
+    pub fn arity(self) -> RangeInclusive<usize> {
+        match self {
+            Op::Not => 1..=1,
+            Op::Mux => 3..=3,
+            _ => 2..=usize::MAX,
+        }
     }
 
-    fn select_style(&self) -> &'static str {
-        match self.op_name.as_str() {
-            "OR" => "diamond",
-            "XOR" => "trapezium",
-            "AND" => "circle",
-            _ => unreachable!(),
+    /// Whether the operation takes any number of inputs, written between them, e.g. `a AND b`.
+    /// All others are written in front of their inputs, e.g. `NOT a`.
+    pub fn is_infix(self) -> bool {
+        *self.arity().end() == usize::MAX
+    }
+
+    /// Result for `inputs` in the order of [`Gate::inputs`], missing inputs count as 0.
+    pub fn apply(self, inputs: impl IntoIterator<Item = Num>) -> Num {
+        let mut inputs = inputs.into_iter();
+        match self {
+            Op::And => inputs.fold(!0, |lhs, rhs| lhs & rhs),
+            Op::Or => inputs.fold(0, |lhs, rhs| lhs | rhs),
+            Op::Xor => inputs.fold(0, |lhs, rhs| lhs ^ rhs),
+            Op::Nand => !Op::And.apply(inputs),
+            Op::Nor => !Op::Or.apply(inputs),
+            Op::Xnor => !Op::Xor.apply(inputs),
+            Op::Not => !inputs.next().unwrap_or(0),
+            Op::Mux => {
+                let [select, low, high] = [(); 3].map(|_| inputs.next().unwrap_or(0));
+                (select & high) | (!select & low)
+            }
         }
     }
 }
 
-fn or(lhs: Num, rhs: Num) -> Num {
-    lhs | rhs
+impl Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Op {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Op::ALL
+            .into_iter()
+            .find(|op| op.name() == s)
+            .with_context(|| format!("Unknown gate type `{s}`"))
+    }
 }
 
-fn xor(lhs: Num, rhs: Num) -> Num {
-    lhs ^ rhs
+#[derive(Clone, Debug)]
+pub struct Gate {
+    pub out: String,
+    pub inputs: Vec<String>,
+    pub op: Op,
 }
 
-fn and(lhs: Num, rhs: Num) -> Num {
-    lhs & rhs
+impl Gate {
+    pub fn new(op: Op, inputs: &[&str], out: &str) -> Result<Self> {
+        let arity = op.arity();
+        if !arity.contains(&inputs.len()) {
+            if op.is_infix() {
+                bail!(
+                    "`{op}` takes at least {} inputs, found {}",
+                    arity.start(),
+                    inputs.len()
+                );
+            }
+            let plural = if *arity.start() == 1 { "" } else { "s" };
+            bail!(
+                "`{op}` takes {} input{plural}, found {}",
+                arity.start(),
+                inputs.len()
+            );
+        }
+        Ok(Self {
+            out: out.to_owned(),
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            op,
+        })
+    }
+
+    fn select_style(&self) -> &'static str {
+        match self.op {
+            Op::Or => "diamond",
+            Op::Xor => "trapezium",
+            Op::And => "circle",
+            Op::Nand => "doublecircle",
+            Op::Nor => "Mdiamond",
+            Op::Xnor => "invtrapezium",
+            Op::Not => "invtriangle",
+            Op::Mux => "box",
+        }
+    }
 }
 
+/// Parses the syntax of the puzzle input, `in1 OP in2 -> out`, which is extended to more inputs
+/// as `in1 OP in2 OP in3 -> out`, and to operations with a fixed number of inputs as
+/// `OP in1 in2 in3 -> out`.
 impl FromStr for Gate {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re =
-            regex!(r"(?<in1>[\w\d]{3}) (?<gate>OR|AND|XOR) (?<in2>[\w\d]{3}) -> (?<out>[\w\d]{3})");
-        let captured = re.captures(s).unwrap();
-        let in1 = captured.name("in1").unwrap().as_str().to_owned();
-        let in2 = captured.name("in2").unwrap().as_str().to_owned();
-        let out = captured.name("out").unwrap().as_str().to_owned();
-        let op_name = captured.name("gate").unwrap().as_str().to_owned();
-        let op = match op_name.as_str() {
-            "XOR" => xor,
-            "OR" => or,
-            "AND" => and,
-            g => panic!("Unexpected gate type: {g}"),
+        let (lhs, out) = s
+            .split_once(" -> ")
+            .with_context(|| format!("Expected `<inputs> -> <output>`, found `{s}`"))?;
+        let tokens = lhs.split_whitespace().collect::<Vec<_>>();
+        let (op, inputs) = match tokens.as_slice() {
+            [] => bail!("Gate without inputs"),
+            [op, inputs @ ..] if op.parse::<Op>().is_ok() || inputs.len() % 2 == 1 => {
+                let op = op.parse::<Op>()?;
+                if op.is_infix() {
+                    bail!("`{op}` has to be written between its inputs");
+                }
+                (op, inputs.to_vec())
+            }
+            [_, op, ..] => {
+                let op = op.parse::<Op>()?;
+                if !op.is_infix() {
+                    bail!("`{op}` has to be written in front of its inputs");
+                }
+                if tokens
+                    .iter()
+                    .skip(1)
+                    .step_by(2)
+                    .any(|other| *other != op.name())
+                {
+                    bail!("Expected the same gate type between all inputs, found `{lhs}`");
+                }
+                (op, tokens.iter().copied().step_by(2).collect())
+            }
+            [input] => bail!("Expected a gate type for `{input}`"),
         };
+        for wire in inputs.iter().chain([&out.trim()]) {
+            if !wire.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                bail!("Invalid wire name `{wire}`");
+            }
+        }
+        Self::new(op, &inputs, out.trim())
+    }
+}
 
-        Ok(Self {
-            out,
-            in1,
-            in2,
-            op_name,
-            op,
-        })
+/// The gate in the syntax of the puzzle input, see [`Gate::from_str`].
+impl Display for Gate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.op.is_infix() {
+            write!(f, "{}", self.inputs.join(&format!(" {} ", self.op)))?;
+        } else {
+            write!(f, "{} {}", self.op, self.inputs.join(" "))?;
+        }
+        write!(f, " -> {}", self.out)
     }
 }
 
 #[aoc_generator(day24)]
-pub fn input_generator(input: &str) -> Input {
-    let (inputs, gates) = input.split_once("\n\n").unwrap();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(24, input);
+    let (inputs, gates) = ctx.split_once(input, "\n\n")?;
     let inputs = inputs
         .lines()
-        .map(|line| (line.split_once(": ").unwrap()))
-        .map(|(gid, inp)| (gid.to_owned(), inp.parse::<Num>().unwrap()))
-        .collect();
+        .map(|line| {
+            let (gid, inp) = ctx.split_once(line, ": ")?;
+            Ok((gid.to_owned(), ctx.parse::<Num>(inp)?))
+        })
+        .collect::<Result<_, ParseError>>()?;
     let gates = gates
         .lines()
-        .map(|line| Gate::from_str(line).unwrap())
-        .map(|gate| (gate.out.clone(), gate))
-        .collect();
-    (inputs, gates)
+        .map(|line| Gate::from_str(line).map_err(|err| ctx.error(line, format!("{err:#}"))))
+        .map_ok(|gate| (gate.out.clone(), gate))
+        .collect::<Result<_, ParseError>>()?;
+    Ok((inputs, gates))
 }
 
-fn compute_bits(inputs: &FxHashMap<String, usize>, gates: &FxHashMap<String, Gate>) -> Vec<Num> {
-    let mut inputs = inputs.clone();
-    gates
-        .keys()
-        .filter(|gate| gate.starts_with("z"))
-        .sorted()
-        .rev()
-        .map(|gate| gates[gate].execute(&mut inputs, gates))
-        .collect()
+/// Number output on the `z` wires.
+fn compute_output(
+    inputs: &FxHashMap<String, usize>,
+    gates: &FxHashMap<String, Gate>,
+) -> Result<Num> {
+    let circuit = Circuit::new(gates)?;
+    let values = circuit.evaluate(inputs)?;
+    Ok(circuit.read(&values, 'z'))
 }
 
 #[aoc(day24, part1)]
-pub fn solve_part1(input: &Input) -> Output {
+pub fn solve_part1(input: &Input) -> Result<Output> {
     let (inputs, gates) = input;
-    compute_bits(inputs, gates)
-        .iter()
-        .fold(0, |out, bit| (out << 1) | bit)
-}
-
-fn into_num(prefix: char, inputs: &FxHashMap<String, usize>) -> Num {
-    inputs
-        .iter()
-        .filter(|(key, _)| key.starts_with(prefix))
-        .sorted_by(|(key_a, _), (key_b, _)| key_a.cmp(key_b))
-        .rev()
-        .fold(0, |out, (_, val)| (out << 1) | val)
-}
-
-fn save_as_dot(
-    inputs: &FxHashMap<String, usize>,
-    gates: &FxHashMap<String, Gate>,
-    rewire: &FxHashSet<&String>,
-) {
-    let gate_plan = gates
-        .keys()
-        .filter(|gate| gate.starts_with("z"))
-        .map(|gate| gates[gate].print_inputs(gates))
-        .collect::<String>();
-    let mut gate_plan = gate_plan.lines().unique().join("\n");
-
-    let x = into_num('x', inputs);
-
-    let y = into_num('y', inputs);
-
-    let expected_result = x + y;
-    let actual_result = compute_bits(inputs, gates)
-        .iter()
-        .fold(0, |out, val| (out << 1) | val);
-
-    let broken_outs = (0..46)
-        .enumerate()
-        .filter(|(_, shift)| ((expected_result >> shift) & 1) != ((actual_result >> shift) & 1))
-        .map(|(gate, _)| format!("z{gate:0>2}"))
-        .collect::<Vec<String>>();
-
-    gate_plan.push_str(
-        &broken_outs
-            .iter()
-            .map(|broken_gate| format!("{}[color=red]", broken_gate))
-            .join("\n"),
-    );
-
-    gate_plan.push_str(
-        &rewire
-            .iter()
-            .map(|wire| format!("{wire}[style=filled];"))
-            .join("\n"),
-    );
-    let mut dot = String::from("digraph {\n");
-    dot.extend(gate_plan.lines().map(|line| format!("\t{}\n", line)));
-    dot.push('}');
-
-    let mut file = File::create("gate_network.dot").unwrap();
-    file.write_all(&dot.into_bytes()).unwrap();
+    compute_output(inputs, gates)
 }
 
 #[aoc(day24, part2)]
@@ -237,72 +272,58 @@ fn save_as_dot(
 ///
 /// $$z_n + c_n = ( c_n << 1) \lor{} z_n $$
 ///
-/// Check for computations where these rules are violated
+/// Swaps of gate outputs are searched bit by bit, from the lowest broken bit of the sum upwards,
+/// and checked by adding test vectors for every bit, see [`repair::find_swaps`]. The repaired
+/// network is then proven to add all inputs, see [`equivalence::check_adder`].
 ///
-/// A representation of the gate network is stored in the
-/// [dot](https://graphviz.org/doc/info/lang.html) language in
-/// `./target/aoc/aoc-autobuild/gate_network.dot` file. Using dot it can be rendered to a file
-/// (e.g., by calling `dot -Tsvg gate_network.dot > gate_network.map`) where:
-/// * $\oplus{}$ is rendered as a trapezoid
-/// * $\lor{}$ is rendered as a diamond
-/// * $\wedge{}$ is rendered as a circle
-/// * Output (`zxx`) nodes with red borders are nodes outputing the wrong bit for the computation
-/// of $x + y$.
-/// * Nodes that are filled (either grey or red) are nodes which need to be swapped for the correct
-/// result.
-pub fn solve_part2(input: &Input) -> Output2 {
-    let (inputs, gates) = input;
-
-    let mut rewire = FxHashSet::default();
-
-    for (name, gate) in gates {
-        // All but the last output gate (which is the last carry bit and should be an OR) need to be XORs
-        if name.starts_with('z') && gate.op_name != "XOR" && name != "z45" {
-            rewire.insert(name);
-        }
-        // All XOR gates that output z must not be fed by x or y as there needs to be an
-        // intermediate XOR with the carry bit
-        if gate.op_name == "XOR"
-            && !name.starts_with('z')
-            // Does not happen in my input
-            && !['x', 'y'].contains(&gate.in1.chars().next().unwrap())
-            // Does not happen in my input
-            && !['x', 'y'].contains(&gate.in2.chars().next().unwrap())
-        {
-            rewire.insert(name);
-        }
-        // All AND gates except the one accepting the initial x_0 value (there is no carry bit yet)
-        // must feed into an OR
-        if gate.op_name == "AND" && ![&gate.in1, &gate.in2].contains(&&"x00".to_owned()) {
-            for other_gate in gates.values() {
-                if [&other_gate.in1, &other_gate.in2].contains(&name) && other_gate.op_name != "OR"
-                {
-                    rewire.insert(name);
-                }
-            }
-        }
-        // All XOR gates must feed into either an XOR or an AND
-        if gate.op_name == "XOR" {
-            for other_gate in gates.values() {
-                if [&other_gate.in1, &other_gate.in2].contains(&name)
-                    && !(other_gate.op_name == "XOR" || other_gate.op_name == "AND")
-                {
-                    rewire.insert(name);
-                }
-            }
-        }
+/// [`Dot`] exports the network with the swapped wires highlighted, for checking the result.
+pub fn solve_part2(input: &Input) -> Result<Output2> {
+    let (_, gates) = input;
+    let swaps = repair::find_swaps(gates, 4)?;
+    let mut repaired = gates.clone();
+    for (lhs, rhs) in &swaps {
+        repair::swap_outputs(&mut repaired, lhs, rhs)?;
+    }
+    let verdict = equivalence::check_adder(&repaired)?;
+    if verdict != equivalence::Verdict::Equivalent {
+        bail!("The repaired network is not an adder: {verdict}");
     }
+    Ok(swaps
+        .iter()
+        .flat_map(|(lhs, rhs)| [lhs, rhs])
+        .sorted()
+        .join(","))
+}
 
-    save_as_dot(inputs, gates, &rewire);
-    rewire.iter().sorted().join(",")
+pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
+    solve_part1(&input_generator(input)?)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
+    solve_part2(&input_generator(input)?)
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: usize = 24;
+    const TITLE: &'static str = "Crossed Wires";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output2;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        solve_part1(input)
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        solve_part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -375,16 +396,72 @@ tnw OR pbm -> gnj"
 
     #[test]
     fn samples1_part1() {
-        assert_eq!(4, solve_part1(&input_generator(sample1())));
+        assert_eq!(
+            4,
+            solve_part1(&input_generator(sample1()).unwrap()).unwrap()
+        );
     }
 
     #[test]
     fn samples2_part1() {
-        assert_eq!(2024, solve_part1(&input_generator(sample2())));
+        assert_eq!(
+            2024,
+            solve_part1(&input_generator(sample2()).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_gates() {
+        let gate = |text: &str| text.parse::<Gate>().map(|gate| gate.to_string());
+        for text in [
+            "x00 AND y00 -> z00",
+            "a XNOR b XNOR c -> d",
+            "NOT a -> b",
+            "MUX s a b -> c",
+        ] {
+            assert_eq!(text, gate(text).unwrap());
+        }
+        let error = |text| format!("{:#}", gate(text).unwrap_err());
+        assert_eq!("Unknown gate type `IMPLIES`", error("a IMPLIES b -> c"));
+        assert_eq!(
+            "`NOT` has to be written in front of its inputs",
+            error("a NOT b -> c")
+        );
+        assert_eq!(
+            "`OR` has to be written between its inputs",
+            error("OR a b -> c")
+        );
+        assert_eq!("`MUX` takes 3 inputs, found 2", error("MUX a b -> c"));
+        assert_eq!(
+            "Expected the same gate type between all inputs, found `a AND b OR c`",
+            error("a AND b OR c -> d")
+        );
+    }
+
+    #[test]
+    fn evaluates_all_gates() {
+        assert_eq!(0b1000, Op::And.apply([0b1100, 0b1010, 0b1001]) & 0b1111);
+        assert_eq!(0b0111, Op::Nand.apply([0b1100, 0b1010, 0b1001]) & 0b1111);
+        assert_eq!(0b0001, Op::Nor.apply([0b1100, 0b1010]) & 0b1111);
+        assert_eq!(0b1001, Op::Xnor.apply([0b1100, 0b1010]) & 0b1111);
+        assert_eq!(0b0011, Op::Not.apply([0b1100]) & 0b1111);
+        assert_eq!(0b1010, Op::Mux.apply([0b1100, 0b0110, 0b1010]));
     }
 
     #[test]
-    fn samples1_part2() {
-        assert!(true)
+    fn repairs_adder() {
+        let mut gates = repair::ripple_carry_adder(45);
+        for (lhs, rhs) in [
+            ("z05", "b05"),
+            ("s12", "a12"),
+            ("z20", "a20"),
+            ("z33", "b33"),
+        ] {
+            repair::swap_outputs(&mut gates, lhs, rhs).unwrap();
+        }
+        assert_eq!(
+            "a12,a20,b05,b33,s12,z05,z20,z33",
+            solve_part2(&(FxHashMap::default(), gates)).unwrap()
+        );
     }
 }
@@ -0,0 +1,559 @@
+//! Gate networks as structural Verilog and as BLIF, for exchanging them with logic synthesis and
+//! verification tools.
+//!
+//! Only the subset written here is read back: Verilog modules of gate primitives and of `assign`
+//! statements with the conditional operator for multiplexers, and BLIF models whose `.names`
+//! tables compute one of the functions of [`Op`].
+use anyhow::{bail, Context, Result};
+use fxhash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
+
+use super::{Gate, Op};
+
+/// Most inputs of a `.names` table read, as the truth table is built with all rows.
+const MAX_TABLE_INPUTS: usize = 16;
+
+/// Wires of a network: read by gates only, driven by gates only, and both.
+struct Ports<'a> {
+    inputs: Vec<&'a str>,
+    outputs: Vec<&'a str>,
+    internal: Vec<&'a str>,
+}
+
+impl<'a> Ports<'a> {
+    fn new(gates: &'a FxHashMap<String, Gate>) -> Self {
+        let read = gates
+            .values()
+            .flat_map(|gate| gate.inputs.iter().map(String::as_str))
+            .collect::<FxHashSet<_>>();
+        let inputs = read
+            .iter()
+            .copied()
+            .filter(|wire| !gates.contains_key(*wire))
+            .sorted()
+            .collect();
+        let (internal, outputs) = gates
+            .keys()
+            .map(String::as_str)
+            .sorted()
+            .partition(|wire| read.contains(wire));
+        Self {
+            inputs,
+            outputs,
+            internal,
+        }
+    }
+}
+
+fn sorted_gates(gates: &FxHashMap<String, Gate>) -> impl Iterator<Item = &Gate> {
+    gates.values().sorted_by_key(|gate| &gate.out)
+}
+
+/// Module `name` with a gate primitive per gate, and an `assign` statement per [`Op::Mux`]. Wires
+/// only read are its inputs, wires only driven its outputs.
+pub fn to_verilog(gates: &FxHashMap<String, Gate>, name: &str) -> String {
+    let ports = Ports::new(gates);
+    let mut verilog = format!(
+        "module {name}({});\n",
+        ports.inputs.iter().chain(&ports.outputs).join(", ")
+    );
+    for (kind, wires) in [
+        ("input", &ports.inputs),
+        ("output", &ports.outputs),
+        ("wire", &ports.internal),
+    ] {
+        if !wires.is_empty() {
+            verilog.push_str(&format!("  {kind} {};\n", wires.join(", ")));
+        }
+    }
+    for (idx, gate) in sorted_gates(gates).enumerate() {
+        if let (Op::Mux, [select, low, high]) = (gate.op, gate.inputs.as_slice()) {
+            verilog.push_str(&format!(
+                "  assign {} = {select} ? {high} : {low};\n",
+                gate.out
+            ));
+        } else {
+            verilog.push_str(&format!(
+                "  {} g{idx}({}, {});\n",
+                gate.op.name().to_lowercase(),
+                gate.out,
+                gate.inputs.join(", ")
+            ));
+        }
+    }
+    verilog.push_str("endmodule\n");
+    verilog
+}
+
+/// Statements of `source` with the line they start on, without comments.
+fn statements(source: &str) -> Vec<(usize, String)> {
+    let mut statements = vec![];
+    let mut statement = String::new();
+    let mut start = None;
+    let mut in_block_comment = false;
+    for (idx, line) in source.lines().enumerate() {
+        let mut rest = line;
+        while !rest.is_empty() {
+            if in_block_comment {
+                match rest.split_once("*/") {
+                    Some((_, after)) => {
+                        in_block_comment = false;
+                        rest = after;
+                    }
+                    None => rest = "",
+                }
+                continue;
+            }
+            let end = ["//", "/*", ";"]
+                .iter()
+                .filter_map(|token| Some((rest.find(token)?, *token)))
+                .min();
+            let (code, token) = match end {
+                Some((pos, token)) => (&rest[..pos], Some(token)),
+                None => (rest, None),
+            };
+            if !code.trim().is_empty() {
+                start.get_or_insert(idx + 1);
+                statement.push_str(code);
+                statement.push(' ');
+            }
+            rest = match token {
+                Some("//") | None => "",
+                Some("/*") => {
+                    in_block_comment = true;
+                    &rest[code.len() + 2..]
+                }
+                Some(_) => {
+                    statements.push((start.take().unwrap_or(idx + 1), statement.trim().to_owned()));
+                    statement.clear();
+                    &rest[code.len() + 1..]
+                }
+            };
+        }
+    }
+    if !statement.trim().is_empty() {
+        statements.push((start.unwrap_or(1), statement.trim().to_owned()));
+    }
+    statements
+}
+
+/// Whether `keyword` is a Verilog gate primitive for an [`Op`].
+fn is_primitive(keyword: &str) -> bool {
+    Op::ALL
+        .iter()
+        .any(|op| *op != Op::Mux && op.name().to_lowercase() == keyword)
+}
+
+/// Reads a module written by [`to_verilog`]. Every wire has to be declared, the ports of the
+/// module as `input` or `output`.
+pub fn from_verilog(source: &str) -> Result<FxHashMap<String, Gate>> {
+    let ports_re = regex!(r"^\w*\s*\(([\w\s,]*)\)$");
+    let mux_re = regex!(r"^(\w+)\s*=\s*(\w+)\s*\?\s*(\w+)\s*:\s*(\w+)$");
+    let mut declared = FxHashSet::default();
+    let mut gates = FxHashMap::default();
+    let mut add = |line: usize, gate: Gate, declared: &FxHashSet<String>| {
+        for wire in gate.inputs.iter().chain([&gate.out]) {
+            if !declared.contains(wire) {
+                bail!("Line {line}: Undeclared wire `{wire}`");
+            }
+        }
+        if gates.contains_key(&gate.out) {
+            bail!("Line {line}: Wire `{}` is driven twice", gate.out);
+        }
+        gates.insert(gate.out.clone(), gate);
+        Ok(())
+    };
+    let mut io = FxHashSet::default();
+    // Line of the `module` statement and its ports
+    let mut module: Option<(usize, Vec<String>)> = None;
+    for (line, statement) in statements(source) {
+        // `endmodule` has no semicolon and ends up in front of the next statement
+        let (ended, statement) = match statement.strip_prefix("endmodule") {
+            Some(rest) => (true, rest.trim()),
+            None => (false, statement.as_str()),
+        };
+        if ended {
+            let Some((start, ports)) = module.take() else {
+                bail!("Line {line}: `endmodule` without `module`");
+            };
+            if !statement.is_empty() {
+                bail!("Line {line}: Statement after `endmodule`");
+            }
+            if let Some(port) = ports.iter().find(|port| !io.contains(*port)) {
+                bail!("Line {start}: Port `{port}` is not declared as `input` or `output`");
+            }
+            continue;
+        }
+        let (keyword, rest) = statement
+            .split_once(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or((statement, ""));
+        match keyword {
+            "module" if module.is_none() => {
+                let Some(caps) = ports_re.captures(rest.trim()) else {
+                    bail!("Line {line}: Expected `module <name>(<ports>)`");
+                };
+                let ports = caps[1]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|port| !port.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+                module = Some((line, ports));
+            }
+            _ if module.is_none() => bail!("Line {line}: Statement outside of a module"),
+            "input" | "output" | "wire" => {
+                let wires = rest
+                    .split(',')
+                    .map(|wire| wire.trim().to_owned())
+                    .collect_vec();
+                if keyword != "wire" {
+                    io.extend(wires.iter().cloned());
+                }
+                declared.extend(wires);
+            }
+            "assign" => {
+                let Some(caps) = mux_re.captures(rest.trim()) else {
+                    bail!("Line {line}: Expected `assign <out> = <select> ? <high> : <low>`");
+                };
+                let gate = Gate::new(Op::Mux, &[&caps[2], &caps[4], &caps[3]], &caps[1])
+                    .with_context(|| format!("Line {line}"))?;
+                add(line, gate, &declared)?;
+            }
+            primitive if is_primitive(primitive) => {
+                let op = primitive.to_uppercase().parse::<Op>()?;
+                let Some(caps) = ports_re.captures(rest.trim()) else {
+                    bail!("Line {line}: Expected `{primitive} <name>(<out>, <inputs>)`");
+                };
+                let ports = caps[1].split(',').map(str::trim).collect::<Vec<_>>();
+                let gate =
+                    Gate::new(op, &ports[1..], ports[0]).with_context(|| format!("Line {line}"))?;
+                add(line, gate, &declared)?;
+            }
+            other => bail!("Line {line}: Unsupported statement `{other}`"),
+        }
+    }
+    if module.is_some() {
+        bail!("Missing `endmodule`");
+    }
+    Ok(gates)
+}
+
+/// Truth table of `op` with `inputs` inputs, with the first input as the most significant bit of
+/// the row.
+fn truth_table(op: Op, inputs: usize) -> Vec<bool> {
+    (0..1 << inputs)
+        .map(|row: usize| op.apply((0..inputs).rev().map(|bit| (row >> bit) & 1)) & 1 == 1)
+        .collect()
+}
+
+/// Model `name` with a `.names` table per gate, listing the rows for which the output is 1.
+/// Wires only read are its inputs, wires only driven its outputs.
+pub fn to_blif(gates: &FxHashMap<String, Gate>, name: &str) -> String {
+    let ports = Ports::new(gates);
+    let mut blif = format!(
+        ".model {name}\n.inputs {}\n.outputs {}\n",
+        ports.inputs.join(" "),
+        ports.outputs.join(" ")
+    );
+    for gate in sorted_gates(gates) {
+        blif.push_str(&format!(".names {} {}\n", gate.inputs.join(" "), gate.out));
+        let inputs = gate.inputs.len();
+        for (row, _) in truth_table(gate.op, inputs)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, on)| *on)
+        {
+            blif.push_str(&format!("{row:0inputs$b} 1\n"));
+        }
+    }
+    blif.push_str(".end\n");
+    blif
+}
+
+/// Whether the cube, e.g. `1-0`, covers the row `row` of the truth table.
+fn covers(cube: &str, row: usize) -> bool {
+    let inputs = cube.len();
+    cube.chars().enumerate().all(|(idx, c)| {
+        let bit = (row >> (inputs - 1 - idx)) & 1;
+        c == '-' || (c == '1' && bit == 1) || (c == '0' && bit == 0)
+    })
+}
+
+/// A `.names` table: its line, the wires and the rows with their line, inputs and output.
+type Table<'a> = (usize, Vec<&'a str>, Vec<(usize, &'a str, &'a str)>);
+
+/// Gate computing the function of a `.names` table.
+fn names_gate(line: usize, wires: &[&str], rows: &[(usize, &str, &str)]) -> Result<Gate> {
+    let Some((out, inputs)) = wires.split_last() else {
+        bail!("Line {line}: Expected `.names <inputs> <out>`");
+    };
+    if inputs.len() > MAX_TABLE_INPUTS {
+        bail!("Line {line}: Tables with more than {MAX_TABLE_INPUTS} inputs are not supported");
+    }
+    if rows.iter().map(|(_, _, value)| value).dedup().count() > 1 {
+        bail!("Line {line}: Table mixes rows for output 0 and 1");
+    }
+    // Rows either list where the output is 1, or all where it is 0
+    let mut on_set = true;
+    let mut table = vec![false; 1 << inputs.len()];
+    for (row_line, cube, value) in rows {
+        if cube.len() != inputs.len() || cube.chars().any(|c| !"01-".contains(c)) {
+            bail!(
+                "Line {row_line}: Expected a cube of {} inputs, found `{cube}`",
+                inputs.len()
+            );
+        }
+        on_set = match *value {
+            "1" => true,
+            "0" => false,
+            _ => bail!("Line {row_line}: Expected output 0 or 1, found `{value}`"),
+        };
+        for (row, entry) in table.iter_mut().enumerate() {
+            *entry |= covers(cube, row);
+        }
+    }
+    if !on_set {
+        table.iter_mut().for_each(|entry| *entry = !*entry);
+    }
+    let Some(op) = Op::ALL
+        .into_iter()
+        .find(|op| op.arity().contains(&inputs.len()) && truth_table(*op, inputs.len()) == table)
+    else {
+        bail!("Line {line}: Table for `{out}` is not a supported gate");
+    };
+    Gate::new(op, inputs, out)
+}
+
+/// Reads a model written by [`to_blif`]. `#` starts a comment, `\` at the end of a line continues
+/// it on the next.
+pub fn from_blif(source: &str) -> Result<FxHashMap<String, Gate>> {
+    // Joins continued lines, keeping the number of their first line
+    let mut lines: Vec<(usize, String)> = vec![];
+    let mut continued = false;
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let (line, continues) = match line.trim_end().strip_suffix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        match lines.last_mut() {
+            Some((_, last)) if continued => {
+                last.push(' ');
+                last.push_str(line);
+            }
+            _ => lines.push((idx + 1, line.to_owned())),
+        }
+        continued = continues;
+    }
+
+    let mut gates = FxHashMap::default();
+    let mut names: Option<Table> = None;
+    let mut finish = |names: Option<Table>| {
+        if let Some((line, wires, rows)) = names {
+            let gate = names_gate(line, &wires, &rows)?;
+            if gates.contains_key(&gate.out) {
+                bail!("Line {line}: Wire `{}` is driven twice", gate.out);
+            }
+            gates.insert(gate.out.clone(), gate);
+        }
+        Ok(())
+    };
+    let mut ended = false;
+    for (line, text) in &lines {
+        let mut words = text.split_whitespace();
+        let Some(first) = words.next() else {
+            continue;
+        };
+        if ended {
+            bail!("Line {line}: Text after `.end`");
+        }
+        if !first.starts_with('.') {
+            let Some((_, _, rows)) = &mut names else {
+                bail!("Line {line}: Table row outside of `.names`");
+            };
+            let Some((cube, value)) = text.split_whitespace().collect_tuple() else {
+                bail!("Line {line}: Expected `<inputs> <output>`");
+            };
+            rows.push((*line, cube, value));
+            continue;
+        }
+        finish(names.take())?;
+        match first {
+            ".model" | ".inputs" | ".outputs" => {}
+            ".names" => names = Some((*line, words.collect(), vec![])),
+            ".end" => ended = true,
+            other => bail!("Line {line}: Unsupported command `{other}`"),
+        }
+    }
+    finish(names)?;
+    Ok(gates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::repair::ripple_carry_adder;
+    use super::*;
+
+    fn listing(gates: &FxHashMap<String, Gate>) -> Vec<String> {
+        gates
+            .values()
+            .map(|gate| gate.to_string())
+            .sorted()
+            .collect()
+    }
+
+    fn gates() -> FxHashMap<String, Gate> {
+        "x00 XOR y00 -> z00\nx00 AND y00 -> c00\nc00 OR x01 -> z01"
+            .lines()
+            .map(|line| line.parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect()
+    }
+
+    #[test]
+    fn writes_verilog() {
+        assert_eq!(
+            "module adder(x00, x01, y00, z00, z01);
+  input x00, x01, y00;
+  output z00, z01;
+  wire c00;
+  and g0(c00, x00, y00);
+  xor g1(z00, x00, y00);
+  or g2(z01, c00, x01);
+endmodule
+",
+            to_verilog(&gates(), "adder")
+        );
+    }
+
+    #[test]
+    fn writes_blif() {
+        assert_eq!(
+            ".model adder
+.inputs x00 x01 y00
+.outputs z00 z01
+.names x00 y00 c00
+11 1
+.names x00 y00 z00
+01 1
+10 1
+.names c00 x01 z01
+01 1
+10 1
+11 1
+.end
+",
+            to_blif(&gates(), "adder")
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let gates = ripple_carry_adder(6);
+        let verilog = from_verilog(&to_verilog(&gates, "adder")).unwrap();
+        assert_eq!(listing(&gates), listing(&verilog));
+        let blif = from_blif(&to_blif(&gates, "adder")).unwrap();
+        assert_eq!(listing(&gates), listing(&blif));
+    }
+
+    #[test]
+    fn round_trips_without_ports() {
+        let gates = FxHashMap::default();
+        let verilog = to_verilog(&gates, "empty");
+        assert_eq!("module empty();\nendmodule\n", verilog);
+        assert!(from_verilog(&verilog).unwrap().is_empty());
+
+        // Wires driven by each other, without any inputs
+        let gates = "NOT b -> a\nNOT a -> b\na AND b -> z"
+            .lines()
+            .map(|line| line.parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect::<FxHashMap<_, _>>();
+        let verilog = to_verilog(&gates, "loop");
+        assert!(verilog.starts_with("module loop(z);\n  output z;\n  wire a, b;\n"));
+        assert_eq!(listing(&gates), listing(&from_verilog(&verilog).unwrap()));
+    }
+
+    #[test]
+    fn round_trips_all_gates() {
+        let gates = "a NAND b NAND c -> d\nd NOR a -> e\ne XNOR b -> f\nNOT f -> g\nMUX g a b -> h"
+            .lines()
+            .map(|line| line.parse::<Gate>().unwrap())
+            .map(|gate| (gate.out.clone(), gate))
+            .collect::<FxHashMap<_, _>>();
+        let verilog = to_verilog(&gates, "all");
+        assert!(verilog.contains("  nand g0(d, a, b, c);\n"));
+        assert!(verilog.contains("  assign h = g ? b : a;\n"));
+        assert_eq!(listing(&gates), listing(&from_verilog(&verilog).unwrap()));
+        let blif = to_blif(&gates, "all");
+        assert!(blif.contains(".names g a b h\n010 1\n011 1\n101 1\n111 1\n"));
+        assert_eq!(listing(&gates), listing(&from_blif(&blif).unwrap()));
+    }
+
+    #[test]
+    fn reads_other_spellings() {
+        let verilog = "// half adder
+module half(a, b, s, c);
+  input a, /* first */ b;
+  output s,
+    c;
+  xor (s, a, b); and g1 (c, a, b);
+endmodule";
+        assert_eq!(
+            vec!["a AND b -> c", "a XOR b -> s"],
+            listing(&from_verilog(verilog).unwrap())
+        );
+
+        let blif = ".model half # comment
+.inputs a b
+.outputs s c
+.names a b \\
+  s
+00 0
+11 0
+.names a b c
+0- 0
+-0 0
+.end";
+        assert_eq!(
+            vec!["a AND b -> c", "a XOR b -> s"],
+            listing(&from_blif(blif).unwrap())
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let verilog = |source| format!("{:#}", from_verilog(source).unwrap_err());
+        assert_eq!(
+            "Line 3: Undeclared wire `c`",
+            verilog("module m(a, b);\ninput a, b;\nand g(c, a, b);\nendmodule")
+        );
+        assert_eq!(
+            "Line 3: Unsupported statement `buf`",
+            verilog("module m(a, b);\ninput a; output b;\nbuf g(b, a);\nendmodule")
+        );
+        assert_eq!(
+            "Line 2: `NOT` takes 1 input, found 2",
+            verilog("module m(a, b, c);\nnot g(c, a, b);\nendmodule")
+        );
+        assert_eq!("Missing `endmodule`", verilog("module m();"));
+        assert_eq!(
+            "Line 1: Port `c` is not declared as `input` or `output`",
+            verilog("module m(a, b, c);\ninput a; output b;\nwire c;\nendmodule")
+        );
+
+        let blif = |source| from_blif(source).unwrap_err().to_string();
+        assert_eq!(
+            "Line 2: Table for `c` is not a supported gate",
+            blif(".model m\n.names a b c\n01 1\n.end")
+        );
+        assert_eq!(
+            "Line 2: Table row outside of `.names`",
+            blif(".model m\n11 1\n.end")
+        );
+        assert_eq!(
+            vec!["a AND b -> s"],
+            listing(&from_blif(".model m\n.names a b\\\ns\n11 1\n.end").unwrap())
+        );
+    }
+}
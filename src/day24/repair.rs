@@ -0,0 +1,305 @@
+use std::fmt::Display;
+
+use fxhash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
+
+use super::{
+    circuit::{Circuit, WireId},
+    Gate, Num, Op,
+};
+
+/// Gates of a ripple-carry adder for `width` bit numbers on the wires `x00`, `y00`, ..., with
+/// the sum on `z00` to `z<width>`.
+///
+/// Bit 0 is a half adder, all other bits are full adders. Internal wires are named after the
+/// bit they belong to: `s` for the sum without carry, `a` and `b` for the two terms of the
+/// carry and `c` for the carry.
+pub fn ripple_carry_adder(width: usize) -> FxHashMap<String, Gate> {
+    let name = |prefix: char, bit: usize| format!("{prefix}{bit:02}");
+    let carry = |bit: usize| {
+        if bit + 1 == width {
+            name('z', width)
+        } else {
+            name('c', bit)
+        }
+    };
+    let mut gates = vec![];
+    for bit in 0..width {
+        let (x, y) = (name('x', bit), name('y', bit));
+        if bit == 0 {
+            gates.push(Gate::new(Op::Xor, &[&x, &y], &name('z', 0)));
+            gates.push(Gate::new(Op::And, &[&x, &y], &carry(0)));
+            continue;
+        }
+        let (sum, carry_in) = (name('s', bit), carry(bit - 1));
+        gates.extend([
+            Gate::new(Op::Xor, &[&x, &y], &sum),
+            Gate::new(Op::Xor, &[&sum, &carry_in], &name('z', bit)),
+            Gate::new(Op::And, &[&x, &y], &name('a', bit)),
+            Gate::new(Op::And, &[&sum, &carry_in], &name('b', bit)),
+            Gate::new(Op::Or, &[&name('a', bit), &name('b', bit)], &carry(bit)),
+        ]);
+    }
+    gates
+        .into_iter()
+        .map(|gate| {
+            let gate = gate.unwrap();
+            (gate.out.clone(), gate)
+        })
+        .collect()
+}
+
+/// Why no set of swaps turns a network into an adder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepairError {
+    /// The network does not have `x` and `y` inputs of the same width, with one more `z` output.
+    Shape { x: usize, y: usize, z: usize },
+    /// No combination of at most `pairs` swaps made bit `bit` of the sum correct.
+    Unrepairable { bit: usize, pairs: usize },
+}
+
+impl Display for RepairError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepairError::Shape { x, y, z } => write!(
+                f,
+                "Not an adder: {x} x bits and {y} y bits, but {z} z bits"
+            ),
+            RepairError::Unrepairable { bit, pairs } => write!(
+                f,
+                "Bit {bit} of the sum can't be repaired by swapping at most {pairs} pairs of outputs"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RepairError {}
+
+/// Additions `(x, y)` testing bit `bit`: all combinations of the two input bits, with and
+/// without a carry from the bit below.
+fn test_vectors(bit: usize) -> impl Iterator<Item = (Num, Num)> {
+    let carries = if bit == 0 {
+        vec![0]
+    } else {
+        vec![0, 1 << (bit - 1)]
+    };
+    carries.into_iter().flat_map(move |carry| {
+        [(0, 0), (0, 1), (1, 0), (1, 1)].map(|(x, y)| (carry | (x << bit), carry | (y << bit)))
+    })
+}
+
+/// The buses of a network that passed the shape check.
+pub(super) struct Adder {
+    pub(super) x: Vec<WireId>,
+    pub(super) y: Vec<WireId>,
+    pub(super) z: Vec<WireId>,
+}
+
+impl Adder {
+    pub(super) fn new(circuit: &Circuit) -> Result<Self, RepairError> {
+        let (x, y, z) = (circuit.bus('x'), circuit.bus('y'), circuit.bus('z'));
+        if x.len() != y.len() || z.len() != x.len() + 1 {
+            return Err(RepairError::Shape {
+                x: x.len(),
+                y: y.len(),
+                z: z.len(),
+            });
+        }
+        Ok(Self { x, y, z })
+    }
+
+    fn add(&self, circuit: &Circuit, values: &mut [Num], x: Num, y: Num) -> Num {
+        for (bit, (x_wire, y_wire)) in self.x.iter().zip(&self.y).enumerate() {
+            values[*x_wire] = (x >> bit) & 1;
+            values[*y_wire] = (y >> bit) & 1;
+        }
+        circuit.run(values);
+        self.z
+            .iter()
+            .rev()
+            .fold(0, |out, wire| (out << 1) | (values[*wire] & 1))
+    }
+
+    /// Lowest wrong bit of the sum over the test vectors of all bits, `None` if `circuit` adds
+    /// correctly.
+    fn first_broken_bit(&self, circuit: &Circuit) -> Option<usize> {
+        let mut values = vec![0; circuit.wire_count()];
+        (0..self.x.len()).find_map(|bit| {
+            test_vectors(bit)
+                .map(|(x, y)| self.add(circuit, &mut values, x, y) ^ (x + y))
+                .filter(|wrong| *wrong != 0)
+                .map(|wrong| wrong.trailing_zeros() as usize)
+                .min()
+        })
+    }
+
+    /// Swaps outputs until all bits are correct, depth first. `Err` has the highest bit reached.
+    ///
+    /// Only tries swaps that fix the lowest broken bit without breaking one below. As the bits
+    /// below are correct, their wires are assumed to be correct as well, so one of the swapped
+    /// wires has to feed the broken bit or the one above, where its carry ends up.
+    fn search(
+        &self,
+        circuit: &Circuit,
+        pairs: usize,
+        swaps: &mut Vec<(WireId, WireId)>,
+    ) -> Result<(), usize> {
+        let Some(bit) = self.first_broken_bit(circuit) else {
+            return Ok(());
+        };
+        if pairs == 0 {
+            return Err(bit);
+        }
+        let verified = self.z[..bit]
+            .iter()
+            .flat_map(|wire| circuit.fan_in(*wire))
+            .collect::<FxHashSet<_>>();
+        let local = self.z[bit..(bit + 2).min(self.z.len())]
+            .iter()
+            .flat_map(|wire| circuit.fan_in(*wire))
+            .filter(|wire| !verified.contains(wire))
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        let others = circuit
+            .outputs()
+            .filter(|wire| !verified.contains(wire))
+            .sorted()
+            .collect::<Vec<_>>();
+
+        let mut reached = bit;
+        for (lhs, rhs) in local.iter().cartesian_product(&others) {
+            // Pairs inside of `local` are tried once
+            if lhs == rhs || (local.contains(rhs) && rhs < lhs) {
+                continue;
+            }
+            let Ok(swapped) = circuit.with_swapped(*lhs, *rhs) else {
+                continue;
+            };
+            if self.first_broken_bit(&swapped).is_some_and(|n| n <= bit) {
+                continue;
+            }
+            swaps.push((*lhs, *rhs));
+            match self.search(&swapped, pairs - 1, swaps) {
+                Ok(()) => return Ok(()),
+                Err(n) => reached = reached.max(n),
+            }
+            swaps.pop();
+        }
+        Err(reached)
+    }
+}
+
+/// Finds at most `pairs` pairs of gates whose outputs need to be exchanged to make the network an
+/// adder of its `x` and `y` inputs, verified by adding test vectors for every bit.
+///
+/// Returns the names of the swapped wires, each pair ordered by name.
+pub fn find_swaps(
+    gates: &FxHashMap<String, Gate>,
+    pairs: usize,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let circuit = Circuit::new(gates)?;
+    let adder = Adder::new(&circuit)?;
+    let mut swaps = vec![];
+    adder
+        .search(&circuit, pairs, &mut swaps)
+        .map_err(|bit| RepairError::Unrepairable { bit, pairs })?;
+    Ok(swaps
+        .into_iter()
+        .map(|(lhs, rhs)| {
+            let (lhs, rhs) = (circuit.name(lhs), circuit.name(rhs));
+            (lhs.min(rhs).to_owned(), lhs.max(rhs).to_owned())
+        })
+        .collect())
+}
+
+/// Exchanges the outputs of the gates driving `lhs` and `rhs`.
+pub fn swap_outputs(
+    gates: &mut FxHashMap<String, Gate>,
+    lhs: &str,
+    rhs: &str,
+) -> anyhow::Result<()> {
+    let (Some(mut lhs_gate), Some(mut rhs_gate)) = (gates.remove(lhs), gates.remove(rhs)) else {
+        anyhow::bail!("Only outputs of gates can be swapped, not {lhs} and {rhs}");
+    };
+    lhs_gate.out = rhs.to_owned();
+    rhs_gate.out = lhs.to_owned();
+    gates.insert(rhs.to_owned(), lhs_gate);
+    gates.insert(lhs.to_owned(), rhs_gate);
+    Ok(())
+}
+
+/// Whether `gates` adds its `x` and `y` inputs for the test vectors of all bits.
+pub fn is_adder(gates: &FxHashMap<String, Gate>) -> anyhow::Result<bool> {
+    let circuit = Circuit::new(gates)?;
+    Ok(Adder::new(&circuit)?.first_broken_bit(&circuit).is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_adders() {
+        for width in 1..10 {
+            let gates = ripple_carry_adder(width);
+            assert!(is_adder(&gates).unwrap(), "width {width}");
+        }
+        assert_eq!(5 * 8 - 3, ripple_carry_adder(8).len());
+    }
+
+    #[test]
+    fn finds_swaps() {
+        let mut gates = ripple_carry_adder(12);
+        swap_outputs(&mut gates, "z03", "b03").unwrap();
+        swap_outputs(&mut gates, "s06", "a06").unwrap();
+        swap_outputs(&mut gates, "z10", "b10").unwrap();
+        assert!(!is_adder(&gates).unwrap());
+        let swaps = find_swaps(&gates, 3).unwrap();
+        for (lhs, rhs) in &swaps {
+            swap_outputs(&mut gates, lhs, rhs).unwrap();
+        }
+        assert!(is_adder(&gates).unwrap());
+        assert_eq!(3, swaps.len());
+        assert!(swaps.contains(&("b03".to_owned(), "z03".to_owned())));
+    }
+
+    #[test]
+    fn repairs_top_carry() {
+        let mut gates = ripple_carry_adder(8);
+        gates.insert(
+            "dup".to_owned(),
+            Gate::new(Op::And, &["a07", "b07"], "dup").unwrap(),
+        );
+        swap_outputs(&mut gates, "z08", "dup").unwrap();
+        assert_eq!(
+            vec![("dup".to_owned(), "z08".to_owned())],
+            find_swaps(&gates, 1).unwrap()
+        );
+
+        gates.remove("dup");
+        assert_eq!(
+            "Bit 8 of the sum can't be repaired by swapping at most 1 pairs of outputs",
+            find_swaps(&gates, 1).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn reports_unrepairable_bits() {
+        let mut gates = ripple_carry_adder(8);
+        gates.insert(
+            "c04".to_owned(),
+            Gate::new(Op::And, &["a04", "b04"], "c04").unwrap(),
+        );
+        assert_eq!(
+            "Bit 5 of the sum can't be repaired by swapping at most 2 pairs of outputs",
+            find_swaps(&gates, 2).unwrap_err().to_string()
+        );
+
+        gates.remove("z08");
+        assert_eq!(
+            "Not an adder: 8 x bits and 8 y bits, but 8 z bits",
+            find_swaps(&gates, 2).unwrap_err().to_string()
+        );
+    }
+}
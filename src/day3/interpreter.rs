@@ -0,0 +1,162 @@
+use super::lexer::{Lexer, Token};
+
+/// What the instructions of a program work on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    pub total: i64,
+    disabled: bool,
+    /// Conditions of the open conditional regions, innermost last.
+    regions: Vec<bool>,
+}
+
+impl State {
+    /// Whether instructions run, i.e. they are enabled and all conditional regions around them
+    /// hold.
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled && self.regions.iter().all(|condition| *condition)
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.disabled = !enabled;
+    }
+
+    /// Opens a region inside the current one, in which instructions only run if `condition`
+    /// holds.
+    pub fn open_region(&mut self, condition: bool) {
+        self.regions.push(condition);
+    }
+
+    /// Closes the innermost region, does nothing if no region is open.
+    pub fn close_region(&mut self) {
+        self.regions.pop();
+    }
+
+    /// Number of open regions.
+    pub fn depth(&self) -> usize {
+        self.regions.len()
+    }
+}
+
+/// Runs an instruction with the arguments of its call.
+pub type Run = fn(&mut State, &[i64]);
+
+#[derive(Clone, Debug)]
+struct Instruction {
+    name: &'static str,
+    arity: usize,
+    /// Runs even if instructions are disabled, to be able to enable them again.
+    control: bool,
+    run: Run,
+}
+
+/// Runs the instruction calls found in corrupted memory, with the instructions registered by
+/// the caller.
+#[derive(Clone, Debug, Default)]
+pub struct Interpreter {
+    instructions: Vec<Instruction>,
+}
+
+impl Interpreter {
+    /// An interpreter without any instructions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `mul(a,b)` adds the product of its arguments to the total.
+    pub fn multiplications() -> Self {
+        Self::new().with_instruction("mul", 2, |state, args| {
+            state.total += args[0] * args[1];
+        })
+    }
+
+    /// [`Interpreter::multiplications`] that `don't()` disables and `do()` enables again.
+    pub fn conditional_multiplications() -> Self {
+        Self::multiplications()
+            .with_control("do", 0, |state, _| state.set_enabled(true))
+            .with_control("don't", 0, |state, _| state.set_enabled(false))
+    }
+
+    /// Adds an instruction taking `arity` arguments, which only runs while instructions are
+    /// enabled. Replaces an instruction of the same name.
+    pub fn with_instruction(self, name: &'static str, arity: usize, run: Run) -> Self {
+        self.with(Instruction {
+            name,
+            arity,
+            control: false,
+            run,
+        })
+    }
+
+    /// Adds an instruction taking `arity` arguments, which always runs, e.g. to enable the other
+    /// instructions. Replaces an instruction of the same name.
+    pub fn with_control(self, name: &'static str, arity: usize, run: Run) -> Self {
+        self.with(Instruction {
+            name,
+            arity,
+            control: true,
+            run,
+        })
+    }
+
+    fn with(mut self, instruction: Instruction) -> Self {
+        self.instructions
+            .retain(|other| other.name != instruction.name);
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Lexer for calls of the registered instructions.
+    pub fn lexer(&self) -> Lexer {
+        Lexer::new(
+            self.instructions
+                .iter()
+                .map(|instruction| (instruction.name, instruction.arity)),
+        )
+    }
+
+    /// Runs the calls of `program` in order, starting with instructions enabled.
+    pub fn run(&self, program: &str) -> State {
+        let mut state = State::default();
+        for token in self.lexer().tokens(program) {
+            self.step(&mut state, &token);
+        }
+        state
+    }
+
+    /// Runs a single call, if its instruction is enabled.
+    pub fn step(&self, state: &mut State, token: &Token) {
+        let Some(instruction) = self
+            .instructions
+            .iter()
+            .find(|instruction| instruction.name == token.name)
+        else {
+            return;
+        };
+        if instruction.control || state.is_enabled() {
+            (instruction.run)(state, &token.args);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_registered_instructions() {
+        let interpreter = Interpreter::conditional_multiplications()
+            .with_instruction("add", 2, |state, args| state.total += args[0] + args[1])
+            .with_instruction("sub", 2, |state, args| state.total -= args[0] - args[1])
+            .with_control("if", 1, |state, args| state.open_region(args[0] != 0))
+            .with_control("end", 0, |state, _| state.close_region());
+        let state = interpreter
+            .run("add(1,2)if(0)mul(5,5)if(1)add(7,7)end()end()sub(9,4)if(1)don't()mul(2,2)");
+        assert_eq!(3 - 5, state.total);
+        assert_eq!(1, state.depth());
+        assert!(!state.is_enabled());
+
+        let state = interpreter.run("if(1)if(0)end()mul(2,3)end()end()mul(1,2)");
+        assert_eq!(8, state.total);
+        assert_eq!(0, state.depth());
+    }
+}
@@ -0,0 +1,108 @@
+use std::ops::Range;
+
+use itertools::Itertools;
+
+/// Longest number accepted as an argument.
+const MAX_DIGITS: usize = 3;
+
+/// An instruction call `name(arg,...)` found in the corrupted memory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub name: &'static str,
+    pub args: Vec<i64>,
+    /// Byte range of the whole call in the input.
+    pub span: Range<usize>,
+}
+
+/// Finds calls of known instructions, skipping everything else as garbage.
+///
+/// A call is the name of an instruction directly followed by its arguments in parentheses,
+/// separated by commas without any spaces. Arguments are numbers of 1 to 3 digits and calls
+/// need exactly the number of arguments the instruction takes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lexer {
+    /// Names with the number of arguments, longest name first.
+    instructions: Vec<(&'static str, usize)>,
+}
+
+impl Lexer {
+    pub fn new(instructions: impl IntoIterator<Item = (&'static str, usize)>) -> Self {
+        Self {
+            instructions: instructions
+                .into_iter()
+                .sorted_by_key(|(name, _)| std::cmp::Reverse(name.len()))
+                .collect(),
+        }
+    }
+
+    /// Tokens of `input` in order. Garbage is skipped a character at a time, so calls can start
+    /// in the middle of it, e.g. `do_not_mul(5,5)` contains a call of `mul`.
+    pub fn tokens<'a>(&'a self, input: &'a str) -> impl Iterator<Item = Token> + 'a {
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            while pos < input.len() {
+                let start = pos;
+                if let Some((token, end)) = self.call(input, start) {
+                    pos = end;
+                    return Some(token);
+                }
+                pos += input[start..].chars().next().map_or(1, char::len_utf8);
+            }
+            None
+        })
+    }
+
+    /// The call starting at byte `start`, with the byte after it.
+    fn call(&self, input: &str, start: usize) -> Option<(Token, usize)> {
+        let rest = &input[start..];
+        self.instructions.iter().find_map(|(name, arity)| {
+            let mut text = rest.strip_prefix(name)?.strip_prefix('(')?;
+            let mut args = Vec::with_capacity(*arity);
+            for idx in 0..*arity {
+                if idx > 0 {
+                    text = text.strip_prefix(',')?;
+                }
+                let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+                if !(1..=MAX_DIGITS).contains(&digits) {
+                    return None;
+                }
+                args.push(text[..digits].parse().ok()?);
+                text = &text[digits..];
+            }
+            text = text.strip_prefix(')')?;
+            let end = input.len() - text.len();
+            Some((
+                Token {
+                    name,
+                    args,
+                    span: start..end,
+                },
+                end,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_calls() {
+        let lexer = Lexer::new([("mul", 2), ("do", 0), ("don't", 0)]);
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(1234,8)undo()?mul(8,5,1)";
+        assert_eq!(
+            vec![
+                ("mul", vec![2, 4], 1..9),
+                ("don't", vec![], 20..27),
+                ("mul", vec![5, 5], 28..36),
+                ("do", vec![], 61..65),
+            ],
+            lexer
+                .tokens(input)
+                .map(|token| (token.name, token.args, token.span))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, lexer.tokens("mul(1, 2)ÿdo (").count());
+    }
+}
@@ -1,59 +1,62 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
-#[allow(unused)]
-use itertools::Itertools;
+
+use crate::error::ParseError;
+use crate::solution::Solution;
+
+mod interpreter;
+mod lexer;
+
+pub use interpreter::{Interpreter, Run, State};
+pub use lexer::{Lexer, Token};
 
 type Output = i64;
 type Input = String;
 
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Input {
-    input.to_owned()
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Ok(input.to_owned())
 }
 
 #[aoc(day3, part1)]
 pub fn solve_part1(input: &Input) -> Output {
-    let re = regex!(r"mul\((\d{1,3}),(\d{1,3})\)");
-    re.captures_iter(input)
-        .map(|cap| cap[1].parse::<i64>().unwrap() * cap[2].parse::<i64>().unwrap())
-        .sum()
+    Interpreter::multiplications().run(input).total
 }
 
-#[derive(Debug)]
-enum ToDo {
-    Do(i64),
-    Dont(i64),
+#[aoc(day3, part2)]
+pub fn solve_part2(input: &Input) -> Output {
+    Interpreter::conditional_multiplications().run(input).total
 }
 
-impl ToDo {
-    fn inner(&self) -> i64 {
-        match self {
-            ToDo::Do(num) => *num,
-            ToDo::Dont(num) => *num,
-        }
-    }
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-#[aoc(day3, part2)]
-pub fn solve_part2(input: &Input) -> Output {
-    let re = regex!(r"(?:mul\((\d{1,3}),(\d{1,3})\))|(don't\(\))|(do\(\))");
-    re.captures_iter(input)
-        .fold(ToDo::Do(0), |state, cap| match (state, &cap[0][..3]) {
-            (ToDo::Do(sum), "mul") => {
-                ToDo::Do(cap[1].parse::<i64>().unwrap() * cap[2].parse::<i64>().unwrap() + sum)
-            }
-            (ToDo::Do(sum), "don") => ToDo::Dont(sum),
-            (ToDo::Dont(sum), "do(") => ToDo::Do(sum),
-            (state, _) => state,
-        })
-        .inner()
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
-}
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: usize = 3;
+    const TITLE: &'static str = "Mull It Over";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -64,9 +67,12 @@ mod tests {
     fn samples_part1() {
         assert_eq!(
             161,
-            solve_part1(&input_generator(
-                "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"
-            ))
+            solve_part1(
+                &input_generator(
+                    "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"
+                )
+                .unwrap()
+            )
         );
     }
 
@@ -74,9 +80,12 @@ mod tests {
     fn samples_part2() {
         assert_eq!(
             48,
-            solve_part2(&input_generator(
-                "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
-            ))
+            solve_part2(
+                &input_generator(
+                    "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"
+                )
+                .unwrap()
+            )
         );
     }
 }
@@ -1,19 +1,25 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 use pathfinding::matrix::Matrix;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Input = Matrix<char>;
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Input {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(4, input);
+    ctx.check_rectangular(input)?;
     Matrix::from_rows(
         input
             .lines()
             .map(|line| line.chars().collect::<Vec<char>>()),
     )
-    .unwrap()
+    .map_err(|err| ctx.error(input, err))
 }
 
 #[aoc(day4, part1)]
@@ -89,12 +95,35 @@ fn is_x_mas(pos: (usize, usize), matrix: &Input) -> bool {
             && neighbours[7] == 'M')
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: usize = 4;
+    const TITLE: &'static str = "Ceres Search";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -116,11 +145,11 @@ MXMXAXMASX"
 
     #[test]
     fn samples_part1() {
-        assert_eq!(18, solve_part1(&input_generator(sample())));
+        assert_eq!(18, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(9, solve_part2(&input_generator(sample())));
+        assert_eq!(9, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
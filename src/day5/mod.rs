@@ -5,6 +5,9 @@ use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = usize;
 type Book = Vec<usize>;
 type Input = (Vec<Rule>, Vec<Book>);
@@ -32,34 +35,28 @@ impl FromStr for Rule {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let numbers = s
-            .split('|')
-            .map(|n| n.parse::<usize>().context("Failed to parse number"))
-            .collect::<Result<Vec<usize>, Self::Err>>()?;
+        let (before, after) = s.split_once('|').context("No `|` delimiter")?;
         Ok(Rule {
-            before: numbers[0],
-            after: numbers[1],
+            before: before.parse().context("Failed to parse number")?,
+            after: after.parse().context("Failed to parse number")?,
         })
     }
 }
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> Result<Input> {
-    let input = input.split("\n\n").collect::<Vec<&str>>();
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(5, input);
+    let (rules, books) = ctx.split_once(input, "\n\n")?;
 
-    let rules = input[0]
+    let rules = rules
         .lines()
-        .map(Rule::from_str)
-        .collect::<Result<Vec<Rule>>>()?;
+        .map(|line| Rule::from_str(line).map_err(|err| ctx.error(line, format!("{err:#}"))))
+        .collect::<Result<Vec<Rule>, ParseError>>()?;
 
-    let books = input[1]
+    let books = books
         .lines()
-        .map(|line| {
-            line.split(',')
-                .map(|n| n.parse::<usize>().context("Failed to parse number"))
-                .collect::<Result<Vec<usize>>>()
-        })
-        .collect::<Result<Vec<Vec<usize>>>>()?;
+        .map(|line| line.split(',').map(|n| ctx.parse::<usize>(n)).collect())
+        .collect::<Result<Vec<Vec<usize>>, ParseError>>()?;
 
     Ok((rules, books))
 }
@@ -101,12 +98,35 @@ fn order(a: usize, b: usize, rules: &[Rule]) -> std::cmp::Ordering {
     }
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input).unwrap())
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input).unwrap())
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Print Queue";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
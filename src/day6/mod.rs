@@ -1,76 +1,45 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use crate::utils::point::Point;
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{direction::Direction, grid::Grid, point::Point};
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
 type Output = usize;
-type Input = HashMap<Point, char>;
+type Input = Grid<char>;
 
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Input {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.chars()
-                .enumerate()
-                .map(move |(x, c)| (Point::from((x as isize, y as isize)), c))
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(6, input);
+    let map = Grid::parse(&ctx, input, |c| ['.', '#', '^'].contains(&c).then_some(c))?;
+    if map.find(&'^').is_none() {
+        return Err(ctx.error(input, "No guard `^` on the map"));
+    }
+    Ok(map)
 }
 
 #[derive(Clone, Debug, PartialEq)]
 enum Outcome {
     Loop,
-    Escape(HashSet<(Point, Dir)>),
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-enum Dir {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Dir {
-    fn turn_right(&self) -> Self {
-        match self {
-            Dir::Up => Dir::Right,
-            Dir::Right => Dir::Down,
-            Dir::Down => Dir::Left,
-            Dir::Left => Dir::Up,
-        }
-    }
+    Escape(HashSet<(Point, Direction)>),
 }
 
-impl From<Dir> for Point {
-    fn from(value: Dir) -> Self {
-        Self::from(match value {
-            Dir::Up => (0, -1),
-            Dir::Right => (1, 0),
-            Dir::Down => (0, 1),
-            Dir::Left => (-1, 0),
-        })
-    }
-}
-
-fn walk(map: &HashMap<Point, char>) -> Outcome {
+fn walk(map: &Grid<char>) -> Outcome {
     let mut visited = HashSet::new();
-    let (start, _) = map.iter().find(|(_, c)| **c == '^').unwrap();
-    let mut pos = *start;
-    let mut dir = Dir::Up;
+    let mut pos = map.find(&'^').unwrap();
+    let mut dir = Direction::Up;
     visited.insert((pos, dir));
 
     loop {
         let new_pos = pos + dir.into();
-        if !map.contains_key(&new_pos) {
+        let Some(tile) = map.get(new_pos) else {
             return Outcome::Escape(visited);
-        }
-        if *map.get(&new_pos).expect("Path contained in map not in map") == '#' {
-            dir = dir.turn_right();
+        };
+        if *tile == '#' {
+            dir = dir.turn_clockwise();
         } else {
             pos = new_pos;
         }
@@ -80,6 +49,26 @@ fn walk(map: &HashMap<Point, char>) -> Outcome {
     }
 }
 
+/// The map after `steps` steps of the guard, with the visited tiles marked `X` and the guard as
+/// an arrow pointing in its direction. Turning counts as a step.
+pub fn render(input: &Input, steps: usize) -> Grid<char> {
+    let mut map = input.clone();
+    let mut pos = map.find(&'^').unwrap();
+    let mut dir = Direction::Up;
+    for _ in 0..steps {
+        match map.get(pos + dir.into()) {
+            None => break,
+            Some('#') => dir = dir.turn_clockwise(),
+            Some(_) => {
+                map[pos] = 'X';
+                pos = pos + dir.into();
+            }
+        }
+    }
+    map[pos] = dir.arrow();
+    map
+}
+
 #[aoc(day6, part1)]
 pub fn solve_part1(input: &Input) -> Output {
     match walk(input) {
@@ -99,21 +88,44 @@ pub fn solve_part2(input: &Input) -> Output {
             .collect::<Vec<Point>>(),
     };
     path.iter()
-        .filter(|pos| *input.get(pos).expect("Path not in map") == '.')
+        .filter(|pos| input[**pos] == '.')
         .filter(|pos| {
             let mut new_map = input.clone();
-            *new_map.get_mut(pos).expect("Path not in map") = '#';
+            new_map[**pos] = '#';
             walk(&new_map) == Outcome::Loop
         })
         .count()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Guard Gallivant";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -136,11 +148,18 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(41, solve_part1(&input_generator(sample())));
+        assert_eq!(41, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(6, solve_part2(&input_generator(sample())));
+        assert_eq!(6, solve_part2(&input_generator(sample()).unwrap()));
+    }
+
+    #[test]
+    fn renders_path() {
+        let map = render(&input_generator(sample()).unwrap(), 100);
+        assert_eq!(40, map.find_all(&'X').count());
+        assert_eq!(Some(Point::from((7, 9))), map.find(&'v'));
     }
 }
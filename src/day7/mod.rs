@@ -7,6 +7,9 @@ use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Output = u64;
 type Input = Vec<Equation>;
 
@@ -59,11 +62,12 @@ impl FromStr for Equation {
 }
 
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Result<Input> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(7, input);
     input
         .lines()
-        .map(Equation::from_str)
-        .collect::<Result<Vec<Equation>>>()
+        .map(|line| Equation::from_str(line).map_err(|err| ctx.error(line, format!("{err:#}"))))
+        .collect::<Result<Vec<Equation>, ParseError>>()
 }
 
 #[aoc(day7, part1)]
@@ -86,12 +90,35 @@ pub fn solve_part2(input: &Input) -> Output {
         .sum()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input).unwrap())
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input).unwrap())
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: usize = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
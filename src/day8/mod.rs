@@ -1,29 +1,28 @@
 use std::collections::{HashMap, HashSet};
 
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
-use crate::utils::point::Point;
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+use crate::utils::{grid::Grid, point::Point};
 
 type Output = usize;
 type Input = (Vec<((Point, Point), Point)>, isize, isize);
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Input {
-    let y_dim = input.lines().count() as isize;
-    let x_dim = input.lines().next().unwrap().len() as isize;
-    let distances = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.chars()
-                .enumerate()
-                .filter(|(_, c)| *c != '.')
-                .map(move |(x, c)| (Point::from((x as isize, y as isize)), c))
-        })
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(8, input);
+    let map = Grid::parse(&ctx, input, |c| {
+        (c == '.' || c.is_ascii_alphanumeric()).then_some(c)
+    })?;
+    let distances = map
+        .iter()
+        .filter(|(_, c)| **c != '.')
         .fold(HashMap::new(), |mut map, (pos, freq)| {
-            map.entry(freq)
+            map.entry(*freq)
                 .and_modify(|positions: &mut Vec<Point>| positions.push(pos))
                 .or_insert(vec![pos]);
             map
@@ -38,7 +37,7 @@ pub fn input_generator(input: &str) -> Input {
         })
         .collect();
 
-    (distances, x_dim, y_dim)
+    Ok((distances, map.width() as isize, map.height() as isize))
 }
 
 #[aoc(day8, part1)]
@@ -89,12 +88,35 @@ pub fn solve_part2(input: &Input) -> Output {
     antinodes.len()
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
+}
+
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -118,11 +140,11 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(14, solve_part1(&input_generator(sample())));
+        assert_eq!(14, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(34, solve_part2(&input_generator(sample())));
+        assert_eq!(34, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
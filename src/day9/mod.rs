@@ -1,7 +1,11 @@
+use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[allow(unused)]
 use itertools::Itertools;
 
+use crate::error::{ParseContext, ParseError};
+use crate::solution::Solution;
+
 type Id = usize;
 type Output = usize;
 type Input = (Vec<Block>, usize);
@@ -13,25 +17,26 @@ pub enum Block {
 }
 
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Input {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let ctx = ParseContext::new(9, input);
+    if input.is_empty() {
+        return Err(ctx.error(input, "Empty disk map"));
+    }
     let mut id = 0;
-    (
-        input
-            .chars()
-            .enumerate()
-            .flat_map(|(idx, c)| {
-                let size = c.to_digit(10).expect("Value in file map not a digit") as usize;
-                if idx % 2 == 0 {
-                    let new_blocks = std::iter::repeat(Block::File(id)).take(size);
-                    id += 1;
-                    new_blocks
-                } else {
-                    std::iter::repeat(Block::Empty).take(size)
-                }
-            })
-            .collect::<Vec<Block>>(),
-        id - 1,
-    )
+    let mut blocks = vec![];
+    for (idx, c) in input.char_indices() {
+        let size = c
+            .to_digit(10)
+            .ok_or_else(|| ctx.error(&input[idx..idx + c.len_utf8()], "Not a digit"))?
+            as usize;
+        if idx % 2 == 0 {
+            blocks.extend(std::iter::repeat_n(Block::File(id), size));
+            id += 1;
+        } else {
+            blocks.extend(std::iter::repeat_n(Block::Empty, size));
+        }
+    }
+    Ok((blocks, id - 1))
 }
 
 #[aoc(day9, part1)]
@@ -104,12 +109,35 @@ pub fn solve_part2(input: &Input) -> Output {
     hash(&defragmented)
 }
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(&input_generator(input))
+pub fn part1(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part1(&input_generator(input)?))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(&input_generator(input))
+pub fn part2(input: &str) -> Result<impl std::fmt::Display, ParseError> {
+    Ok(solve_part2(&input_generator(input)?))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
+
+    type Input = Input;
+    type Output1 = Output;
+    type Output2 = Output;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1> {
+        Ok(solve_part1(input))
+    }
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2> {
+        Ok(solve_part2(input))
+    }
 }
 
 #[cfg(test)]
@@ -122,11 +150,11 @@ mod tests {
 
     #[test]
     fn samples_part1() {
-        assert_eq!(1928, solve_part1(&input_generator(sample())));
+        assert_eq!(1928, solve_part1(&input_generator(sample()).unwrap()));
     }
 
     #[test]
     fn samples_part2() {
-        assert_eq!(2858, solve_part2(&input_generator(sample())));
+        assert_eq!(2858, solve_part2(&input_generator(sample()).unwrap()));
     }
 }
@@ -0,0 +1,205 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+/// Malformed puzzle input, pointing at the offending text.
+///
+/// `line` and `column` count from 1, `column` counts characters and not bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+    source_line: String,
+}
+
+impl ParseError {
+    pub fn new(
+        day: usize,
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        reason: impl Display,
+    ) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            text: text.into(),
+            reason: reason.to_string(),
+            source_line: String::new(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.reason
+        )?;
+        // Errors for whole blocks of the input only point at the first line of the block
+        let snippet = self.text.lines().next().unwrap_or("");
+        if !snippet.is_empty() {
+            write!(f, " (`{snippet}`)")?;
+        }
+        if !self.source_line.is_empty() {
+            let gutter = self.line.to_string().len();
+            let marker = "^".repeat(snippet.chars().count().max(1));
+            write!(
+                f,
+                "\n{:gutter$} |\n{} | {}\n{:gutter$} | {}{marker}",
+                "",
+                self.line,
+                self.source_line,
+                "",
+                " ".repeat(self.column - 1),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The puzzle input of a day, used to locate the text an input generator fails on.
+#[derive(Clone, Copy, Debug)]
+pub struct ParseContext<'a> {
+    day: usize,
+    input: &'a str,
+}
+
+impl<'a> ParseContext<'a> {
+    pub fn new(day: usize, input: &'a str) -> Self {
+        Self { day, input }
+    }
+
+    /// Creates an error for `text`, which has to be a slice of the input. Text from elsewhere is
+    /// reported at the end of the input.
+    pub fn error(&self, text: &str, reason: impl Display) -> ParseError {
+        let start = self.input.as_ptr() as usize;
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| offset + text.len() <= self.input.len())
+            .unwrap_or(self.input.len());
+        let line_start = self.input[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+        let line_end = self.input[offset..]
+            .find('\n')
+            .map_or(self.input.len(), |pos| offset + pos);
+        ParseError {
+            day: self.day,
+            line: self.input[..offset].matches('\n').count() + 1,
+            column: self.input[line_start..offset].chars().count() + 1,
+            text: text.to_owned(),
+            reason: reason.to_string(),
+            source_line: self.input[line_start..line_end].to_owned(),
+        }
+    }
+
+    /// Parses `text`, a slice of the input.
+    pub fn parse<T>(&self, text: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.parse()
+            .map_err(|err| self.error(text, format!("Not a valid {}: {err}", short_name::<T>())))
+    }
+
+    /// Splits `text`, a slice of the input, at the first occurrence of `delimiter`.
+    pub fn split_once(
+        &self,
+        text: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.error(text, format!("No {delimiter:?} delimiter")))
+    }
+
+    /// Checks that all lines of `text`, a slice of the input, have the same length.
+    pub fn check_rectangular(&self, text: &str) -> Result<(), ParseError> {
+        let mut lines = text.lines();
+        let width = lines
+            .next()
+            .ok_or_else(|| self.error(text, "Empty map"))?
+            .len();
+        match lines.find(|line| line.len() != width) {
+            Some(line) => Err(self.error(
+                line,
+                format!("Expected a row of width {width}, found {}", line.len()),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Text not matching a regex, or a named group failing to parse, see `regex_parse!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaptureError {
+    NoMatch,
+    Missing {
+        group: &'static str,
+    },
+    Invalid {
+        group: &'static str,
+        /// Byte range of the group in the parsed text.
+        span: Range<usize>,
+        reason: String,
+    },
+}
+
+impl CaptureError {
+    /// The part of the parsed `text` the error is about.
+    pub fn locate<'a>(&self, text: &'a str) -> &'a str {
+        match self {
+            CaptureError::Invalid { span, .. } => &text[span.clone()],
+            _ => text,
+        }
+    }
+}
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::NoMatch => write!(f, "Does not match the expected format"),
+            CaptureError::Missing { group } => write!(f, "Group `{group}` not captured"),
+            CaptureError::Invalid { group, reason, .. } => write!(f, "Group `{group}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+pub(crate) fn short_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_text() {
+        let input = "1 2\n3 x\n";
+        let ctx = ParseContext::new(1, input);
+        let err = ctx.parse::<i64>(&input[6..7]).unwrap_err();
+        assert_eq!((1, 2, 3), (err.day, err.line, err.column));
+        assert_eq!("x", err.text);
+        assert_eq!(
+            "Day 1, line 2, column 3: Not a valid i64: invalid digit found in string (`x`)
+  |
+2 | 3 x
+  |   ^",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn reports_foreign_text_at_end() {
+        let ctx = ParseContext::new(5, "1|2\n");
+        let err = ctx.error("elsewhere", "Missing section");
+        assert_eq!((2, 1), (err.line, err.column));
+    }
+}
@@ -1,10 +1,10 @@
-#![feature(iter_map_windows)]
 #![feature(iter_array_chunks)]
 
 #[macro_use]
-mod utils;
+pub mod utils;
 use aoc_runner_derive::aoc_lib;
 
+pub mod bench;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -30,5 +30,8 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod error;
+pub mod regression;
+pub mod solution;
 
 aoc_lib! {year = 2024}
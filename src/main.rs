@@ -0,0 +1,190 @@
+use std::{
+    io::Read,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use aoc_2024::{
+    bench::{BenchConfig, BenchReport},
+    regression::{self, AnswerStore, Outcome},
+    solution::{self, Day, REGISTRY},
+};
+
+const USAGE: &str = "Usage: aoc-2024 <day> [1|2|all] [input file|-]
+       aoc-2024 check [--inputs <dir>] [--answers <file>] [--record]
+       aoc-2024 bench [<day>] [--inputs <dir>] [--warmup <n>] [--iterations <n>] [--json <file>]
+
+Runs the solution for <day> (1-25). The part defaults to `all`. The puzzle input is read from the
+given file, or from stdin if the file is `-` or omitted.
+
+`check` solves every day with an input `day<N>.txt` in the input directory (default `input/2024`)
+and compares the answers against the answers file (default `answers.txt`). With `--record` the
+answers of inputs without an expected answer are added to the answers file.
+
+`bench` times parsing and both parts of <day>, or of every day with an input in the input
+directory, separately. The results can also be written as JSON to compare them between commits.";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Part {
+    One,
+    Two,
+    All,
+}
+
+fn parse_args(args: &[String]) -> Result<(&'static dyn Day, Part, Option<&str>)> {
+    let day = args
+        .first()
+        .context("No day given")?
+        .parse::<usize>()
+        .context("Day is not a number")?;
+    let Some(day) = solution::get(day) else {
+        bail!(
+            "Day {day} does not exist, there are {} days",
+            REGISTRY.len()
+        );
+    };
+    let part = match args.get(1).map(String::as_str) {
+        None | Some("all") => Part::All,
+        Some("1") => Part::One,
+        Some("2") => Part::Two,
+        Some(p) => bail!("Unknown part: {p}"),
+    };
+    let path = args.get(2).map(String::as_str).filter(|path| *path != "-");
+    Ok((day, part, path))
+}
+
+fn read_input(path: Option<&str>) -> Result<String> {
+    let mut input = match path {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read input file {path}"))?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read input from stdin")?;
+            input
+        }
+    };
+    input.truncate(solution::trim_input(&input).len());
+    Ok(input)
+}
+
+fn timed<T>(solve: impl Fn() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let answer = solve();
+    (answer, start.elapsed())
+}
+
+/// Value of the option `--<name> <value>`.
+fn option<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>> {
+    match args.iter().position(|arg| *arg == format!("--{name}")) {
+        Some(idx) => Ok(Some(
+            args.get(idx + 1)
+                .with_context(|| format!("No value for --{name}"))?,
+        )),
+        None => Ok(None),
+    }
+}
+
+fn check(args: &[String]) -> Result<()> {
+    let input_dir = option(args, "inputs")?.unwrap_or(regression::DEFAULT_INPUT_DIR);
+    let answers = Path::new(option(args, "answers")?.unwrap_or(regression::DEFAULT_ANSWERS));
+    let mut store = AnswerStore::load(answers)?;
+
+    let reports = regression::check(Path::new(input_dir), &store)?;
+    for report in &reports {
+        println!("{report}");
+    }
+    let count = |matches: fn(&Outcome) -> bool| {
+        reports
+            .iter()
+            .filter(|report| matches(&report.outcome))
+            .count()
+    };
+    let failed = count(|outcome| matches!(outcome, Outcome::Fail { .. } | Outcome::Error(_)));
+    println!(
+        "{} passed, {failed} failed, {} missing",
+        count(|outcome| *outcome == Outcome::Pass),
+        count(|outcome| matches!(outcome, Outcome::Missing(_))),
+    );
+
+    if args.iter().any(|arg| arg == "--record") {
+        regression::record(&reports, &mut store);
+        store.save(answers)?;
+        println!("Recorded answers in {}", answers.display());
+    }
+    if failed > 0 {
+        bail!("{failed} regressions");
+    }
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<()> {
+    let input_dir = Path::new(option(args, "inputs")?.unwrap_or(regression::DEFAULT_INPUT_DIR));
+    let mut config = BenchConfig::default();
+    if let Some(warmup) = option(args, "warmup")? {
+        config.warmup = warmup.parse().context("Warmup is not a number")?;
+    }
+    if let Some(iterations) = option(args, "iterations")? {
+        config.iterations = iterations.parse().context("Iterations is not a number")?;
+    }
+    // Days without an input are only skipped when running all of them
+    let (days, all) = match args.first().filter(|arg| !arg.starts_with("--")) {
+        Some(day) => {
+            let day = day.parse::<usize>().context("Day is not a number")?;
+            let day = solution::get(day).with_context(|| format!("Day {day} does not exist"))?;
+            (vec![day], false)
+        }
+        None => (REGISTRY.to_vec(), true),
+    };
+
+    let mut report = BenchReport {
+        config,
+        days: vec![],
+    };
+    for day in days {
+        let path = input_dir.join(format!("day{}.txt", day.day()));
+        if !path.exists() {
+            if all {
+                continue;
+            }
+            bail!("No input for day {} at {}", day.day(), path.display());
+        }
+        let input = read_input(path.to_str())?;
+        let benchmark = day.bench(&input, &config)?;
+        println!("{benchmark}");
+        report.days.push(benchmark);
+    }
+    if let Some(path) = option(args, "json")? {
+        std::fs::write(path, report.to_json())
+            .with_context(|| format!("Failed to write benchmark report to {path}"))?;
+    }
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    match args[0].as_str() {
+        "check" => return check(&args[1..]),
+        "bench" => return bench(&args[1..]),
+        _ => {}
+    }
+    let (day, part, path) = parse_args(&args).with_context(|| USAGE.to_string())?;
+    let input = read_input(path)?;
+
+    println!("Day {}: {}", day.day(), day.title());
+    if part != Part::Two {
+        let (answer, elapsed) = timed(|| day.part1(&input));
+        println!("Part 1: {} ({elapsed:?})", answer?);
+    }
+    if part != Part::One {
+        let (answer, elapsed) = timed(|| day.part2(&input));
+        println!("Part 2: {} ({elapsed:?})", answer?);
+    }
+    Ok(())
+}
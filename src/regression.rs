@@ -0,0 +1,259 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+use crate::solution::{trim_input, Day, REGISTRY};
+
+/// Directory cargo-aoc downloads the puzzle inputs to.
+pub const DEFAULT_INPUT_DIR: &str = "input/2024";
+pub const DEFAULT_ANSWERS: &str = "answers.txt";
+
+/// FNV-1a hash of a puzzle input, which unlike `DefaultHasher` is stable between Rust versions.
+pub fn hash_input(input: &str) -> u64 {
+    trim_input(input)
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Known answers, keyed by day, part and the hash of the input they belong to.
+///
+/// Stored one answer per line as `<day> <part> <input hash> <answer>`. Empty lines and lines
+/// starting with `#` are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AnswerStore {
+    answers: BTreeMap<(usize, usize, u64), String>,
+}
+
+impl AnswerStore {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read answers from {}", path.display()))?
+            .parse()
+            .with_context(|| format!("Malformed answers file {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write answers to {}", path.display()))
+    }
+
+    pub fn get(&self, day: usize, part: usize, input_hash: u64) -> Option<&str> {
+        self.answers
+            .get(&(day, part, input_hash))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: usize, part: usize, input_hash: u64, answer: String) {
+        self.answers.insert((day, part, input_hash), answer);
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+impl FromStr for AnswerStore {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut store = Self::default();
+        for (idx, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((day, part, hash, answer)) = line.splitn(4, ' ').collect_tuple() else {
+                bail!(
+                    "Line {}: Expected `<day> <part> <input hash> <answer>`",
+                    idx + 1
+                );
+            };
+            store.insert(
+                day.parse()
+                    .with_context(|| format!("Line {}: Day is not a number", idx + 1))?,
+                part.parse()
+                    .with_context(|| format!("Line {}: Part is not a number", idx + 1))?,
+                u64::from_str_radix(hash, 16)
+                    .with_context(|| format!("Line {}: Input hash is not hexadecimal", idx + 1))?,
+                answer.to_owned(),
+            );
+        }
+        Ok(store)
+    }
+}
+
+impl Display for AnswerStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# <day> <part> <input hash> <answer>")?;
+        for ((day, part, hash), answer) in &self.answers {
+            writeln!(f, "{day} {part} {hash:016x} {answer}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// There is no expected answer for the input, contains the computed answer.
+    Missing(String),
+    /// Parsing or solving failed, or panicked.
+    Error(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub day: usize,
+    pub part: usize,
+    pub input: PathBuf,
+    pub input_hash: u64,
+    pub outcome: Outcome,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {:>2} - Part {}: ", self.day, self.part)?;
+        match &self.outcome {
+            Outcome::Pass => write!(f, "PASS"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {expected}, got {actual})")
+            }
+            Outcome::Missing(actual) => write!(f, "MISSING (got {actual})"),
+            Outcome::Error(err) => write!(f, "ERROR: {err}"),
+        }
+    }
+}
+
+fn solve(day: &dyn Day, part: usize, input: &str) -> Result<String, String> {
+    let result = catch_unwind(AssertUnwindSafe(|| match part {
+        1 => day.part1(input),
+        _ => day.part2(input),
+    }));
+    match result {
+        Ok(answer) => answer.map_err(|err| format!("{err:#}")),
+        Err(panic) => Err(panic
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Panicked".to_string())),
+    }
+}
+
+/// Solves every day with an input file `day<N>.txt` in `input_dir` and compares the answers
+/// against `store`. Days without an input file are skipped.
+pub fn check(input_dir: &Path, store: &AnswerStore) -> Result<Vec<Report>> {
+    let mut reports = vec![];
+    for day in REGISTRY {
+        let path = input_dir.join(format!("day{}.txt", day.day()));
+        if !path.exists() {
+            continue;
+        }
+        let input = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read input file {}", path.display()))?;
+        let input = trim_input(&input);
+        let input_hash = hash_input(input);
+        for part in [1, 2] {
+            let outcome = match (
+                solve(day, part, input),
+                store.get(day.day(), part, input_hash),
+            ) {
+                (Err(err), _) => Outcome::Error(err),
+                (Ok(actual), None) => Outcome::Missing(actual),
+                (Ok(actual), Some(expected)) if actual == expected => Outcome::Pass,
+                (Ok(actual), Some(expected)) => Outcome::Fail {
+                    expected: expected.to_owned(),
+                    actual,
+                },
+            };
+            reports.push(Report {
+                day: day.day(),
+                part,
+                input: path.clone(),
+                input_hash,
+                outcome,
+            });
+        }
+    }
+    Ok(reports)
+}
+
+/// Adds the computed answers of all [`Outcome::Missing`] reports to `store`.
+pub fn record(reports: &[Report], store: &mut AnswerStore) {
+    for report in reports {
+        if let Outcome::Missing(answer) = &report.outcome {
+            store.insert(report.day, report.part, report.input_hash, answer.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_round_trip() {
+        let store = "# comment\n1 2 00000000000000ff 31\n17 1 0000000000000001 4,6,3\n"
+            .parse::<AnswerStore>()
+            .unwrap();
+        assert_eq!(Some("31"), store.get(1, 2, 0xff));
+        assert_eq!(Some("4,6,3"), store.get(17, 1, 1));
+        assert_eq!(store, store.to_string().parse().unwrap());
+        assert!("1 2 31".parse::<AnswerStore>().is_err());
+    }
+
+    #[test]
+    fn checks_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-2024-regression-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        std::fs::write(dir.join("day1.txt"), input).unwrap();
+        std::fs::write(dir.join("day3.txt"), "").unwrap();
+
+        let mut store = AnswerStore::default();
+        store.insert(1, 1, hash_input(input), "11".to_string());
+        store.insert(1, 2, hash_input(input), "42".to_string());
+        let reports = check(&dir, &store).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let outcomes = reports
+            .iter()
+            .map(|report| (report.day, report.part, report.outcome.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (1, 1, Outcome::Pass),
+                (
+                    1,
+                    2,
+                    Outcome::Fail {
+                        expected: "42".to_string(),
+                        actual: "31".to_string()
+                    }
+                ),
+                (3, 1, Outcome::Missing("0".to_string())),
+                (3, 2, Outcome::Missing("0".to_string())),
+            ],
+            outcomes
+        );
+    }
+}
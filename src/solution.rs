@@ -0,0 +1,141 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+use crate::{
+    bench::{self, BenchConfig, DayBenchmark},
+    error::ParseError,
+    *,
+};
+
+/// A day of the calendar, split into parsing the puzzle input and solving each part on the parsed
+/// input.
+pub trait Solution {
+    const DAY: usize;
+    const TITLE: &'static str;
+
+    type Input;
+    type Output1: Display;
+    type Output2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn solve_part1(input: &Self::Input) -> Result<Self::Output1>;
+
+    fn solve_part2(input: &Self::Input) -> Result<Self::Output2>;
+}
+
+/// Object safe view on a [`Solution`], so days with different input and output types can be
+/// stored in the [`REGISTRY`].
+pub trait Day: Sync {
+    fn day(&self) -> usize;
+
+    fn title(&self) -> &'static str;
+
+    fn part1(&self, input: &str) -> Result<String>;
+
+    fn part2(&self, input: &str) -> Result<String>;
+
+    /// Times parsing and solving each part separately.
+    fn bench(&self, input: &str, config: &BenchConfig) -> Result<DayBenchmark>;
+}
+
+impl<S> Day for S
+where
+    S: Solution + Sync,
+{
+    fn day(&self) -> usize {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn part1(&self, input: &str) -> Result<String> {
+        Ok(S::solve_part1(&S::parse(input)?)?.to_string())
+    }
+
+    fn part2(&self, input: &str) -> Result<String> {
+        Ok(S::solve_part2(&S::parse(input)?)?.to_string())
+    }
+
+    fn bench(&self, input: &str, config: &BenchConfig) -> Result<DayBenchmark> {
+        // Fail before measuring anything instead of timing errors
+        let parsed = S::parse(input)?;
+        S::solve_part1(&parsed)?;
+        S::solve_part2(&parsed)?;
+        Ok(DayBenchmark {
+            day: S::DAY,
+            title: S::TITLE,
+            parse: bench::measure(config, || S::parse(input)),
+            part1: bench::measure(config, || S::solve_part1(&parsed)),
+            part2: bench::measure(config, || S::solve_part2(&parsed)),
+        })
+    }
+}
+
+/// All days, ordered by their day number.
+pub static REGISTRY: [&dyn Day; 25] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+/// Strips trailing newlines the same way cargo-aoc does before handing the input to a day.
+pub fn trim_input(input: &str) -> &str {
+    input.trim_end_matches('\n')
+}
+
+/// Looks up the solution of `day`, counting from 1.
+pub fn get(day: usize) -> Option<&'static dyn Day> {
+    day.checked_sub(1)
+        .and_then(|idx| REGISTRY.get(idx))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered() {
+        assert!(REGISTRY
+            .iter()
+            .enumerate()
+            .all(|(idx, day)| day.day() == idx + 1));
+    }
+
+    #[test]
+    fn runs_through_registry() {
+        let day = get(1).unwrap();
+        assert_eq!(
+            "11",
+            day.part1("3   4\n4   3\n2   5\n1   3\n3   9\n3   3")
+                .unwrap()
+        );
+        assert!(get(0).is_none() && get(26).is_none());
+    }
+}
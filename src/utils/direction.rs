@@ -0,0 +1,213 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::bail;
+
+use super::point::Point;
+
+/// One of the 4 directions on a map with `y` growing downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting with [`Direction::Up`].
+    pub const ALL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    pub fn turn_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_counterclockwise(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// Least number of quarter turns in either direction to face `other`, between 0 and 2.
+    pub fn turns(self, other: Self) -> usize {
+        let clockwise = (other as usize + 4 - self as usize) % 4;
+        clockwise.min(4 - clockwise)
+    }
+
+    /// Parses an arrow `^`, `>`, `v` or `<`.
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            '>' => Some(Self::Right),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    /// Parses a compass point `N`, `E`, `S` or `W`.
+    pub fn from_compass(c: char) -> Option<Self> {
+        match c {
+            'N' => Some(Self::Up),
+            'E' => Some(Self::Right),
+            'S' => Some(Self::Down),
+            'W' => Some(Self::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Right => '>',
+            Self::Down => 'v',
+            Self::Left => '<',
+        }
+    }
+}
+
+/// Parses an arrow or a compass point.
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match Self::from_arrow(value).or_else(|| Self::from_compass(value)) {
+            Some(dir) => Ok(dir),
+            None => bail!("Unknown direction: {value}"),
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(value: Direction) -> Self {
+        Self::from(match value {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        })
+    }
+}
+
+/// One of the 8 compass directions on a map with `y` growing downwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction8 {
+    /// All directions, clockwise starting with [`Direction8::N`].
+    pub const ALL: [Self; 8] = [
+        Self::N,
+        Self::NE,
+        Self::E,
+        Self::SE,
+        Self::S,
+        Self::SW,
+        Self::W,
+        Self::NW,
+    ];
+
+    /// Turns by 45 degrees.
+    pub fn turn_clockwise(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turns by 45 degrees.
+    pub fn turn_counterclockwise(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    /// Least number of 45 degree turns in either direction to face `other`, between 0 and 4.
+    pub fn turns(self, other: Self) -> usize {
+        let clockwise = (other as usize + 8 - self as usize) % 8;
+        clockwise.min(8 - clockwise)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Self::N,
+            Direction::Right => Self::E,
+            Direction::Down => Self::S,
+            Direction::Left => Self::W,
+        }
+    }
+}
+
+impl FromStr for Direction8 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "N" => Ok(Self::N),
+            "NE" => Ok(Self::NE),
+            "E" => Ok(Self::E),
+            "SE" => Ok(Self::SE),
+            "S" => Ok(Self::S),
+            "SW" => Ok(Self::SW),
+            "W" => Ok(Self::W),
+            "NW" => Ok(Self::NW),
+            d => bail!("Unknown direction: {d}"),
+        }
+    }
+}
+
+impl From<Direction8> for Point {
+    fn from(value: Direction8) -> Self {
+        Self::from(match value {
+            Direction8::N => (0, -1),
+            Direction8::NE => (1, -1),
+            Direction8::E => (1, 0),
+            Direction8::SE => (1, 1),
+            Direction8::S => (0, 1),
+            Direction8::SW => (-1, 1),
+            Direction8::W => (-1, 0),
+            Direction8::NW => (-1, -1),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns_directions() {
+        assert_eq!(Direction::Left, Direction::Up.turn_counterclockwise());
+        assert_eq!(Direction::Up, Direction::Left.turn_clockwise());
+        assert_eq!(Direction::Right, Direction::Left.reverse());
+        assert_eq!(1, Direction::Up.turns(Direction::Left));
+        assert_eq!(2, Direction::Down.turns(Direction::Up));
+        assert_eq!(Direction8::NW, Direction8::N.turn_counterclockwise());
+        assert_eq!(3, Direction8::NE.turns(Direction8::W));
+    }
+
+    #[test]
+    fn parses_directions() {
+        assert_eq!(Direction::Down, Direction::try_from('v').unwrap());
+        assert_eq!(Direction::Left, Direction::try_from('W').unwrap());
+        assert!(Direction::try_from('x').is_err());
+        assert_eq!(Direction8::SW, "SW".parse().unwrap());
+        assert_eq!(Point::from((-1, 1)), Direction8::SW.into());
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{ParseContext, ParseError};
+
+use super::point::Point;
+
+const NEIGHBOURS4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOURS8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Dense, rectangular map stored row by row, indexed by [`Point`] with `x` as column and `y` as
+/// row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Creates a grid from rows of equal length, returns `None` for ragged rows.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Option<Self> {
+        let mut rows = rows.into_iter();
+        let Some(mut cells) = rows.next() else {
+            return Some(Self {
+                width: 0,
+                height: 0,
+                cells: vec![],
+            });
+        };
+        let width = cells.len();
+        let mut height = 1;
+        for row in rows {
+            if row.len() != width {
+                return None;
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses `text`, a slice of the input of `ctx`, with one cell per character. `tile` returns
+    /// `None` for unknown characters, which are reported as errors.
+    pub fn parse(
+        ctx: &ParseContext,
+        text: &str,
+        mut tile: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        ctx.check_rectangular(text)?;
+        let rows = text
+            .lines()
+            .map(|line| {
+                line.char_indices()
+                    .map(|(idx, c)| {
+                        tile(c).ok_or_else(|| {
+                            ctx.error(&line[idx..idx + c.len_utf8()], "Unknown tile")
+                        })
+                    })
+                    .collect::<Result<Vec<T>, ParseError>>()
+            })
+            .collect::<Result<Vec<Vec<T>>, ParseError>>()?;
+        Self::from_rows(rows).ok_or_else(|| ctx.error(text, "Map is not rectangular"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: Point) -> bool {
+        pos.in_map(self.width as isize, self.height as isize)
+    }
+
+    fn offset(&self, pos: Point) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.y() as usize * self.width + pos.x() as usize)
+    }
+
+    fn point(&self, offset: usize) -> Point {
+        Point::from((
+            (offset % self.width) as isize,
+            (offset / self.width) as isize,
+        ))
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.offset(pos).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        self.offset(pos).map(|offset| &mut self.cells[offset])
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|offset| self.point(offset))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(offset, cell)| (self.point(offset), cell))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The up to 4 horizontal and vertical neighbours of `pos` inside the grid.
+    pub fn neighbours(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS4
+            .into_iter()
+            .map(move |dir| pos + Point::from(dir))
+            .filter(|neigh| self.in_bounds(*neigh))
+    }
+
+    /// The up to 8 neighbours of `pos` inside the grid, including diagonals.
+    pub fn neighbours8(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        NEIGHBOURS8
+            .into_iter()
+            .map(move |dir| pos + Point::from(dir))
+            .filter(|neigh| self.in_bounds(*neigh))
+    }
+
+    /// Positions starting at `start` (inclusive) and stepping by `dir` until leaving the grid.
+    pub fn ray(&self, start: Point, dir: Point) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(start), move |pos| Some(*pos + dir))
+            .take_while(|pos| self.in_bounds(*pos))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        self.cells[y * self.width..(y + 1) * self.width].iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    /// Cells from `start` towards the bottom right.
+    pub fn diagonal(&self, start: Point) -> impl Iterator<Item = &T> {
+        self.ray(start, Point::from((1, 1))).map(|pos| &self[pos])
+    }
+
+    /// Cells from `start` towards the bottom left.
+    pub fn anti_diagonal(&self, start: Point) -> impl Iterator<Item = &T> {
+        self.ray(start, Point::from((-1, 1))).map(|pos| &self[pos])
+    }
+
+    /// Position of the first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.cells
+            .iter()
+            .position(|cell| cell == value)
+            .map(|offset| self.point(offset))
+    }
+
+    /// Positions of all cells equal to `value`.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        self.get(index)
+            .unwrap_or_else(|| panic!("Point {index} not in grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("Point {index} not in grid"))
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        let input = "ab.\n.#c";
+        Grid::parse(&ParseContext::new(0, input), input, Some).unwrap()
+    }
+
+    #[test]
+    fn indexes_by_point() {
+        let grid = sample();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!('c', grid[Point::from((2, 1))]);
+        assert_eq!(None, grid.get(Point::from((3, 0))));
+        assert_eq!(Some(Point::from((1, 1))), grid.find(&'#'));
+        assert_eq!("ab.\n.#c", grid.to_string());
+    }
+
+    #[test]
+    fn iterates_neighbours_and_lines() {
+        let grid = sample();
+        assert_eq!(2, grid.neighbours(Point::from((0, 0))).count());
+        assert_eq!(5, grid.neighbours8(Point::from((1, 0))).count());
+        assert_eq!(vec![&'b', &'#'], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(
+            vec![&'a', &'#'],
+            grid.diagonal(Point::from((0, 0))).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&'.', &'#'],
+            grid.anti_diagonal(Point::from((2, 0))).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_tiles() {
+        let input = "..\n.x";
+        let err = Grid::parse(&ParseContext::new(6, input), input, |c| {
+            (c == '.').then_some(c)
+        })
+        .unwrap_err();
+        assert_eq!((2, 2), (err.line, err.column));
+    }
+}
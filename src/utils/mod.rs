@@ -1,4 +1,11 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::{short_name, CaptureError};
+
+pub mod direction;
+pub mod grid;
 pub mod point;
+pub mod render;
 
 #[allow(unused)]
 macro_rules! regex {
@@ -7,3 +14,98 @@ macro_rules! regex {
         RE.get_or_init(|| regex::Regex::new($re).unwrap())
     }};
 }
+
+/// Matches `$text` against the regex literal `$re` and parses the named groups into the fields
+/// of the same name, e.g.
+///
+/// ```ignore
+/// let caps = regex_parse!(r"(?<x>\d+),(?<y>\d+)", text, { x: isize, y: isize })?;
+/// ```
+///
+/// Evaluates to `Result<_, CaptureError>` of a struct with the given fields.
+#[allow(unused)]
+macro_rules! regex_parse {
+    ($re:literal, $text:expr, { $($group:ident: $ty:ty),* $(,)? }) => {{
+        #[allow(dead_code)]
+        struct Captures {
+            $($group: $ty,)*
+        }
+        let text: &str = $text;
+        match regex!($re).captures(text) {
+            Some(captures) => (|| -> Result<Captures, $crate::error::CaptureError> {
+                Ok(Captures {
+                    $($group: $crate::utils::parse_group(&captures, stringify!($group))?,)*
+                })
+            })(),
+            None => Err($crate::error::CaptureError::NoMatch),
+        }
+    }};
+}
+
+#[doc(hidden)]
+pub fn parse_group<T>(captures: &regex::Captures, group: &'static str) -> Result<T, CaptureError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let capture = captures
+        .name(group)
+        .ok_or(CaptureError::Missing { group })?;
+    capture
+        .as_str()
+        .parse()
+        .map_err(|err| CaptureError::Invalid {
+            group,
+            span: capture.range(),
+            reason: format!("Not a valid {}: {err}", short_name::<T>()),
+        })
+}
+
+/// List of values separated by `SEP`, parsable from a single regex group.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Separated<T, const SEP: char = ','>(pub Vec<T>);
+
+impl<T, const SEP: char> FromStr for Separated<T, SEP>
+where
+    T: FromStr,
+{
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(SEP)
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_named_groups() {
+        let caps =
+            regex_parse!(r"(?<x>\d+) (?<list>[\d,]+)", "12 3,4", { x: u8, list: Separated<u8> })
+                .unwrap();
+        assert_eq!(12, caps.x);
+        assert_eq!(vec![3, 4], caps.list.0);
+    }
+
+    #[test]
+    fn reports_failing_group() {
+        let text = "300 3,4";
+        let err = regex_parse!(r"(?<x>\d+) (?<list>[\d,]+)", text, { x: u8, list: Separated<u8> })
+            .err()
+            .unwrap();
+        assert_eq!(
+            "Group `x`: Not a valid u8: number too large to fit in target type",
+            err.to_string()
+        );
+        assert_eq!("300", err.locate(text));
+        assert_eq!(
+            Some(CaptureError::NoMatch),
+            regex_parse!(r"(?<x>\d+)", "x", { x: u8 }).err()
+        );
+    }
+}
@@ -1,164 +1,418 @@
 use std::{
-    fmt::Display,
-    ops::{Index, IndexMut},
+    array,
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Div, Index, IndexMut, Mul, Neg, Rem, Sub},
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct Point {
-    x: isize,
-    y: isize,
+/// Numeric type usable as coordinate of a [`Point`].
+pub trait Scalar:
+    Copy
+    + Debug
+    + Display
+    + PartialEq
+    + Eq
+    + PartialOrd
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Absolute difference, which unlike `self - rhs` does not underflow for unsigned types.
+    fn abs_diff(self, rhs: Self) -> Self {
+        if self < rhs {
+            rhs - self
+        } else {
+            self - rhs
+        }
+    }
 }
 
-impl Display for Point {
+macro_rules! impl_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_scalar!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Point or vector with `N` coordinates of type `T`, by default 2D with `isize` coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T = isize, const N: usize = 2> {
+    coords: [T; N],
+}
+
+impl<T, const N: usize> Display for Point<T, N>
+where
+    T: Display,
+{
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        write!(f, "(")?;
+        for (idx, coord) in self.coords.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{coord}")?;
+        }
+        write!(f, ")")
     }
 }
 
-impl Index<char> for Point {
-    type Output = isize;
+/// Indexes the axes, with `0` being `x`.
+impl<T, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
 
-    fn index(&self, index: char) -> &Self::Output {
-        match index {
-            'x' => &self.x,
-            'y' => &self.y,
-            i => panic!("Index {i} not in Point"),
-        }
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.coords[index]
     }
 }
 
-impl IndexMut<char> for Point {
-    fn index_mut(&mut self, index: char) -> &mut Self::Output {
-        match index {
-            'x' => &mut self.x,
-            'y' => &mut self.y,
-            i => panic!("Index {i} not in Point"),
-        }
+impl<T, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.coords[index]
     }
 }
 
-impl Point {
-    pub fn in_map(&self, x_dim: isize, y_dim: isize) -> bool {
-        (0..x_dim).contains(&self.x) && (0..y_dim).contains(&self.y)
+macro_rules! impl_axes {
+    ($n:literal: $($axis:ident, $axis_mut:ident = $idx:literal),*) => {
+        impl<T> Point<T, $n>
+        where
+            T: Copy,
+        {
+            $(
+                pub fn $axis(&self) -> T {
+                    self.coords[$idx]
+                }
+
+                pub fn $axis_mut(&mut self) -> &mut T {
+                    &mut self.coords[$idx]
+                }
+            )*
+        }
+    };
+}
+
+impl_axes!(1: x, x_mut = 0);
+impl_axes!(2: x, x_mut = 0, y, y_mut = 1);
+impl_axes!(3: x, x_mut = 0, y, y_mut = 1, z, z_mut = 2);
+impl_axes!(4: x, x_mut = 0, y, y_mut = 1, z, z_mut = 2, w, w_mut = 3);
+
+impl<T, const N: usize> Point<T, N>
+where
+    T: Scalar,
+{
+    pub fn new(coords: [T; N]) -> Self {
+        Self { coords }
     }
 
+    pub fn origin() -> Self {
+        Self::new([T::ZERO; N])
+    }
+
+    pub fn coords(&self) -> [T; N] {
+        self.coords
+    }
+
+    fn zip(self, rhs: Self, f: impl Fn(T, T) -> T) -> Self {
+        Self::new(array::from_fn(|idx| f(self.coords[idx], rhs.coords[idx])))
+    }
+
+    /// Component-wise absolute difference.
     pub fn hemming_distance(&self, rhs: Self) -> Self {
-        Self {
-            x: (self.x - rhs.x).abs(),
-            y: (self.y - rhs.y).abs(),
-        }
+        self.zip(rhs, T::abs_diff)
     }
 
-    pub fn turn_counterclockwise(&self) -> Self {
-        Self {
-            x: -self.y,
-            y: self.x,
-        }
+    pub fn manhattan_distance(&self, rhs: Self) -> T {
+        self.hemming_distance(rhs)
+            .coords
+            .into_iter()
+            .fold(T::ZERO, |sum, diff| sum + diff)
     }
 
-    pub fn turn_clockwise(&self) -> Self {
-        Self {
-            x: self.y,
-            y: -self.x,
-        }
+    pub fn chebyshev_distance(&self, rhs: Self) -> T {
+        self.hemming_distance(rhs)
+            .coords
+            .into_iter()
+            .fold(T::ZERO, T::max)
+    }
+
+    pub fn euclidean_distance_squared(&self, rhs: Self) -> T {
+        self.hemming_distance(rhs)
+            .coords
+            .into_iter()
+            .fold(T::ZERO, |sum, diff| sum + diff * diff)
+    }
+
+    pub fn dot(&self, rhs: Self) -> T {
+        self.coords
+            .into_iter()
+            .zip(rhs.coords)
+            .fold(T::ZERO, |sum, (lhs, rhs)| sum + lhs * rhs)
+    }
+
+    /// Component-wise minimum.
+    pub fn min(self, rhs: Self) -> Self {
+        self.zip(rhs, T::min)
+    }
+
+    /// Component-wise maximum.
+    pub fn max(self, rhs: Self) -> Self {
+        self.zip(rhs, T::max)
     }
 
+    /// Adds `rhs` and wraps each coordinate into `0..limits`.
     pub fn wrapping_add(self, rhs: Self, limits: Self) -> Self {
-        Self {
-            x: (self.x + rhs.x).rem_euclid(limits.x),
-            y: (self.y + rhs.y).rem_euclid(limits.y),
-        }
+        (self + rhs).zip(limits, |coord, limit| (coord % limit + limit) % limit)
+    }
+}
+
+impl<T> Point<T, 2>
+where
+    T: Scalar,
+{
+    pub fn in_map(&self, x_dim: T, y_dim: T) -> bool {
+        (T::ZERO..x_dim).contains(&self.x()) && (T::ZERO..y_dim).contains(&self.y())
     }
 }
 
-impl std::ops::Add for Point {
-    type Output = Point;
+impl<T> Point<T, 2>
+where
+    T: Scalar + Neg<Output = T>,
+{
+    pub fn turn_counterclockwise(&self) -> Self {
+        Self::new([-self.y(), self.x()])
+    }
+
+    pub fn turn_clockwise(&self) -> Self {
+        Self::new([self.y(), -self.x()])
+    }
+}
+
+impl<T, const N: usize> Add for Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+        self.zip(rhs, T::add)
     }
 }
 
-impl std::ops::Add<&Self> for &Point {
-    type Output = Point;
+impl<T, const N: usize> Add<&Self> for &Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Point<T, N>;
 
     fn add(self, rhs: &Self) -> Self::Output {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+        *self + **rhs
     }
 }
 
-impl std::ops::Sub<&Point> for &Point {
-    type Output = Point;
+impl<T, const N: usize> Sub for Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Self;
 
-    fn sub(self, rhs: &Point) -> Self::Output {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip(rhs, T::sub)
     }
 }
 
-impl std::ops::Sub for Point {
-    type Output = Point;
+impl<T, const N: usize> Sub<&Point<T, N>> for &Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Point<T, N>;
 
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
+    fn sub(self, rhs: &Point<T, N>) -> Self::Output {
+        *self - *rhs
     }
 }
 
-impl std::ops::Mul<isize> for &Point {
-    type Output = Point;
+impl<T, const N: usize> Mul<T> for Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Self;
 
-    fn mul(self, rhs: isize) -> Self::Output {
-        Point {
-            x: rhs * self.x,
-            y: rhs * self.y,
-        }
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.coords.map(|coord| coord * rhs))
     }
 }
 
-impl std::ops::Mul<isize> for Point {
+impl<T, const N: usize> Mul<T> for &Point<T, N>
+where
+    T: Scalar,
+{
+    type Output = Point<T, N>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl<T, const N: usize> Div<T> for Point<T, N>
+where
+    T: Scalar,
+{
     type Output = Self;
 
-    fn mul(self, rhs: isize) -> Self::Output {
-        Self {
-            x: rhs * self.x,
-            y: rhs * self.y,
-        }
+    fn div(self, rhs: T) -> Self::Output {
+        Self::new(self.coords.map(|coord| coord / rhs))
     }
 }
 
-impl std::ops::Div<isize> for Point {
+impl<T, const N: usize> Neg for Point<T, N>
+where
+    T: Scalar + Neg<Output = T>,
+{
     type Output = Self;
 
-    fn div(self, rhs: isize) -> Self::Output {
+    fn neg(self) -> Self::Output {
+        Self::new(self.coords.map(T::neg))
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(value: [T; N]) -> Self {
+        Self { coords: value }
+    }
+}
+
+impl<T, const N: usize> From<Point<T, N>> for [T; N] {
+    fn from(value: Point<T, N>) -> Self {
+        value.coords
+    }
+}
+
+impl<T> From<Point<T, 2>> for (T, T)
+where
+    T: Copy,
+{
+    fn from(value: Point<T, 2>) -> Self {
+        (value.x(), value.y())
+    }
+}
+
+impl<T> From<(T, T)> for Point<T, 2> {
+    fn from(value: (T, T)) -> Self {
         Self {
-            x: self.x / rhs,
-            y: self.y / rhs,
+            coords: [value.0, value.1],
         }
     }
 }
 
-impl From<Point> for (isize, isize) {
-    fn from(value: Point) -> Self {
-        (value.x, value.y)
+impl<T> From<Point<T, 3>> for (T, T, T)
+where
+    T: Copy,
+{
+    fn from(value: Point<T, 3>) -> Self {
+        (value.x(), value.y(), value.z())
     }
 }
 
-impl From<(isize, isize)> for Point {
-    fn from(value: (isize, isize)) -> Self {
+impl<T> From<(T, T, T)> for Point<T, 3> {
+    fn from(value: (T, T, T)) -> Self {
         Self {
-            x: value.0,
-            y: value.1,
+            coords: [value.0, value.1, value.2],
         }
     }
 }
+
+/// Smallest axis-aligned box containing a set of points, with inclusive bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundingBox<T = isize, const N: usize = 2> {
+    pub min: Point<T, N>,
+    pub max: Point<T, N>,
+}
+
+impl<T, const N: usize> BoundingBox<T, N>
+where
+    T: Scalar,
+{
+    /// Bounding box of `points`, `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Point<T, N>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self {
+            min: first,
+            max: first,
+        };
+        points.for_each(|point| bounds.extend(point));
+        Some(bounds)
+    }
+
+    /// Grows the box to contain `point`.
+    pub fn extend(&mut self, point: Point<T, N>) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    pub fn contains(&self, point: &Point<T, N>) -> bool {
+        (0..N).all(|axis| (self.min[axis]..=self.max[axis]).contains(&point[axis]))
+    }
+
+    /// Number of points along each axis.
+    pub fn size(&self) -> Point<T, N> {
+        self.max - self.min + Point::new([T::ONE; N])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_distances() {
+        let a = Point::<i64, 3>::from((1, -2, 3));
+        let b = Point::from((4, 2, 3));
+        assert_eq!(7, a.manhattan_distance(b));
+        assert_eq!(4, a.chebyshev_distance(b));
+        assert_eq!(25, a.euclidean_distance_squared(b));
+        assert_eq!(9, a.dot(b));
+        assert_eq!(Point::from((1, -2, 3)), a.min(b));
+        assert_eq!(Point::from((4, 2, 3)), a.max(b));
+        assert_eq!(2, Point::<u8, 1>::from([3]).manhattan_distance([5].into()));
+    }
+
+    #[test]
+    fn keeps_2d_behaviour() {
+        let point = Point::from((2, -1));
+        assert_eq!((2, -1), (point.x(), point.y()));
+        assert_eq!(Point::from((1, 2)), point.turn_counterclockwise());
+        assert_eq!(
+            Point::from((0, 4)),
+            point.wrapping_add(Point::from((-3, 0)), Point::from((1, 5)))
+        );
+        assert!(point.turn_counterclockwise().in_map(2, 3));
+        assert!(!point.turn_clockwise().in_map(2, 3));
+        assert_eq!("(2, -1)", point.to_string());
+    }
+
+    #[test]
+    fn bounds_points() {
+        let bounds = BoundingBox::<u128, 4>::from_points([
+            Point::new([1, 5, 0, 2]),
+            Point::new([3, 4, 0, 9]),
+        ])
+        .unwrap();
+        assert_eq!(Point::new([3, 2, 1, 8]), bounds.size());
+        assert!(bounds.contains(&Point::new([2, 4, 0, 9])));
+        assert!(!bounds.contains(&Point::new([2, 3, 0, 9])));
+        assert_eq!(None, BoundingBox::<i32, 2>::from_points([]));
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use super::grid::Grid;
+
+pub type Rgb = [u8; 3];
+
+/// Text frame with one character per cell.
+pub fn ascii<T>(grid: &Grid<T>, palette: impl FnMut(&T) -> char) -> String {
+    grid.map(palette).to_string()
+}
+
+/// Binary PPM (P6) image, with each cell drawn as a `scale` x `scale` square.
+pub fn ppm<T>(grid: &Grid<T>, scale: usize, palette: impl FnMut(&T) -> Rgb) -> Vec<u8> {
+    image("P6", &grid.map(palette), scale)
+}
+
+/// Binary PGM (P5) image, with each cell drawn as a `scale` x `scale` square.
+pub fn pgm<T>(grid: &Grid<T>, scale: usize, mut palette: impl FnMut(&T) -> u8) -> Vec<u8> {
+    image("P5", &grid.map(|cell| [palette(cell)]), scale)
+}
+
+fn image<const C: usize>(magic: &str, pixels: &Grid<[u8; C]>, scale: usize) -> Vec<u8> {
+    let mut image = format!(
+        "{magic}\n{} {}\n255\n",
+        pixels.width() * scale,
+        pixels.height() * scale
+    )
+    .into_bytes();
+    for y in 0..pixels.height() {
+        let row = pixels
+            .row(y)
+            .flat_map(|pixel| pixel.repeat(scale))
+            .collect::<Vec<u8>>();
+        for _ in 0..scale {
+            image.extend(&row);
+        }
+    }
+    image
+}
+
+/// Colours for the map tiles used by the puzzles, unknown tiles are grey.
+pub fn tile_colour(tile: &char) -> Rgb {
+    match tile {
+        '.' => [255, 255, 255],
+        '#' => [40, 40, 40],
+        'O' | '[' | ']' => [160, 110, 60],
+        '@' | 'S' | 'E' | '^' | '>' | 'v' | '<' => [220, 30, 30],
+        'X' => [90, 160, 230],
+        '1'..='9' => [30, 160, 60],
+        _ => [128, 128, 128],
+    }
+}
+
+/// Numbered files `<prefix>0000.<extension>`, `<prefix>0001.<extension>`, ... in a directory,
+/// e.g. the frames of an animation.
+#[derive(Clone, Debug)]
+pub struct Sequence {
+    dir: PathBuf,
+    prefix: String,
+    next: usize,
+}
+
+impl Sequence {
+    pub fn new(dir: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: prefix.into(),
+            next: 0,
+        }
+    }
+
+    /// Writes the next file of the sequence, creating the directory if needed.
+    pub fn push(&mut self, extension: &str, contents: impl AsRef<[u8]>) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create directory {}", self.dir.display()))?;
+        let path = self
+            .dir
+            .join(format!("{}{:04}.{extension}", self.prefix, self.next));
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Failed to write frame {}", path.display()))?;
+        self.next += 1;
+        Ok(path)
+    }
+
+    /// Number of files written.
+    pub fn len(&self) -> usize {
+        self.next
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.next == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<bool> {
+        Grid::from_rows([vec![true, false]]).unwrap()
+    }
+
+    #[test]
+    fn renders_frames() {
+        let grid = sample();
+        assert_eq!("#.", ascii(&grid, |on| if *on { '#' } else { '.' }));
+        assert_eq!(
+            b"P5\n4 2\n255\n\xff\xff\x00\x00\xff\xff\x00\x00".to_vec(),
+            pgm(&grid, 2, |on| if *on { 255 } else { 0 })
+        );
+        let image = ppm(&grid, 1, |on| if *on { [1, 2, 3] } else { [4, 5, 6] });
+        assert_eq!(b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06".to_vec(), image);
+    }
+
+    #[test]
+    fn writes_numbered_files() {
+        let dir = std::env::temp_dir().join(format!("aoc-2024-render-{}", std::process::id()));
+        let mut frames = Sequence::new(&dir, "frame");
+        frames.push("txt", "a").unwrap();
+        let path = frames.push("txt", "b").unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dir.join("frame0001.txt"), path);
+        assert_eq!("b", written);
+        assert_eq!(2, frames.len());
+    }
+}